@@ -0,0 +1,311 @@
+//! Generates `bytecode/generated.rs` metadata from `instructions.in`.
+//!
+//! Opcode properties -- whether a variant carries a jump target, and how much
+//! it moves the stack depth -- used to live as four hand-maintained match
+//! statements (`label_arg()`, `fmt_dis`, the `op_arg_enum!` call sites, and
+//! whatever computed `max_stackdepth`) that drifted apart whenever an opcode
+//! was added. This build script reads the single `instructions.in` table and
+//! emits the match arms for `label_arg()`, `Instruction::stack_effect()` and
+//! its `num_popped`/`num_pushed` decomposition, and `is_adaptive()` into
+//! `$OUT_DIR/instruction_table.rs`, which `bytecode/generated.rs`
+//! `include!`s.
+
+use std::{
+    env, fmt::Write as _, fs, path::Path,
+};
+
+#[derive(Debug)]
+struct OpcodeDef {
+    mnemonic: String,
+    oparg: OpArgKind,
+    stack_effect: StackEffect,
+    popped: StackEffect,
+    adaptive: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum OpArgKind {
+    None,
+    Label,
+    Arg,
+}
+
+#[derive(Debug)]
+enum StackEffect {
+    /// Same net effect whichever edge is taken.
+    Fixed(Term),
+    /// A distinct effect per edge: `(fall_through, taken)`.
+    PerEdge(Term, Term),
+}
+
+/// `N`, `N-arg` or `N+arg` / bare `-arg` / `+arg` (`N` defaulting to `0`).
+#[derive(Debug, Clone, Copy)]
+struct Term {
+    fixed: i32,
+    scaled: Option<bool>, // Some(negate)
+}
+
+impl Term {
+    fn parse(raw: &str, lineno: usize) -> Self {
+        if let Some(rest) = raw.strip_suffix("-arg") {
+            return Self {
+                fixed: parse_fixed(rest, lineno),
+                scaled: Some(true),
+            };
+        }
+        if let Some(rest) = raw.strip_suffix("+arg") {
+            return Self {
+                fixed: parse_fixed(rest, lineno),
+                scaled: Some(false),
+            };
+        }
+        Self {
+            fixed: raw
+                .parse()
+                .unwrap_or_else(|_| panic!("instructions.in:{}: bad stack effect {raw:?}", lineno + 1)),
+            scaled: None,
+        }
+    }
+
+    fn render(&self) -> String {
+        match self.scaled {
+            None => format!("{}", self.fixed),
+            Some(negate) => {
+                let term = if negate { "-(oparg as i32)" } else { "oparg as i32" };
+                if self.fixed == 0 {
+                    term.to_owned()
+                } else {
+                    format!("{} {} {}", self.fixed, if negate { "-" } else { "+" }, term.trim_start_matches('-'))
+                }
+            }
+        }
+    }
+}
+
+fn parse_stack_effect(raw: &str, lineno: usize) -> StackEffect {
+    match raw.split_once(',') {
+        Some((not_taken, taken)) => {
+            StackEffect::PerEdge(Term::parse(not_taken, lineno), Term::parse(taken, lineno))
+        }
+        None => StackEffect::Fixed(Term::parse(raw, lineno)),
+    }
+}
+
+fn parse_fixed(rest: &str, lineno: usize) -> i32 {
+    if rest.is_empty() {
+        0
+    } else {
+        rest.parse()
+            .unwrap_or_else(|_| panic!("instructions.in:{}: bad stack effect prefix {rest:?}", lineno + 1))
+    }
+}
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+    let table_path = Path::new(&manifest_dir).join("instructions.in");
+    println!("cargo:rerun-if-changed={}", table_path.display());
+
+    let source = fs::read_to_string(&table_path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", table_path.display()));
+    let defs = parse(&source);
+
+    let generated = render(&defs);
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let dest = Path::new(&out_dir).join("instruction_table.rs");
+    fs::write(&dest, generated).unwrap_or_else(|e| panic!("failed to write {}: {e}", dest.display()));
+}
+
+fn parse(source: &str) -> Vec<OpcodeDef> {
+    let mut defs = Vec::new();
+    for (lineno, raw) in source.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut cols = line.split_whitespace();
+        let mnemonic = cols
+            .next()
+            .unwrap_or_else(|| panic!("instructions.in:{}: missing mnemonic", lineno + 1))
+            .to_owned();
+        let oparg = match cols
+            .next()
+            .unwrap_or_else(|| panic!("instructions.in:{}: missing oparg kind", lineno + 1))
+        {
+            "none" => OpArgKind::None,
+            "label" => OpArgKind::Label,
+            "arg" => OpArgKind::Arg,
+            other => panic!("instructions.in:{}: unknown oparg kind {other:?}", lineno + 1),
+        };
+        let raw_effect = cols
+            .next()
+            .unwrap_or_else(|| panic!("instructions.in:{}: missing stack effect", lineno + 1));
+        let stack_effect = parse_stack_effect(raw_effect, lineno);
+        let raw_popped = cols
+            .next()
+            .unwrap_or_else(|| panic!("instructions.in:{}: missing popped count", lineno + 1));
+        let popped = parse_stack_effect(raw_popped, lineno);
+        let adaptive = match cols
+            .next()
+            .unwrap_or_else(|| panic!("instructions.in:{}: missing adaptive flag", lineno + 1))
+        {
+            "yes" => true,
+            "no" => false,
+            other => panic!("instructions.in:{}: unknown adaptive flag {other:?}", lineno + 1),
+        };
+        defs.push(OpcodeDef {
+            mnemonic,
+            oparg,
+            stack_effect,
+            popped,
+            adaptive,
+        });
+    }
+    defs
+}
+
+fn render(defs: &[OpcodeDef]) -> String {
+    let mut out = String::new();
+    writeln!(out, "// @generated by build.rs from instructions.in -- do not edit.").unwrap();
+
+    writeln!(out, "impl Instruction {{").unwrap();
+    writeln!(out, "    /// The label this instruction jumps to, if any.").unwrap();
+    writeln!(out, "    pub const fn label_arg(self) -> Option<Label> {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for def in defs {
+        if def.oparg == OpArgKind::Label {
+            writeln!(
+                out,
+                "            Self::{name} {{ target, .. }} => Some(target),",
+                name = def.mnemonic
+            )
+            .unwrap();
+        }
+    }
+    writeln!(out, "            _ => None,").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(
+        out,
+        "    /// Net stack-depth change of executing this instruction with `oparg`, on the\n    /// fall-through edge (`jump == false`) or the taken-jump edge (`jump == true`).\n    /// For non-branching opcodes `jump` is ignored."
+    )
+    .unwrap();
+    writeln!(out, "    pub const fn stack_effect(self, oparg: u32, jump: bool) -> i32 {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for def in defs {
+        let pattern = match def.oparg {
+            OpArgKind::None => format!("Self::{}", def.mnemonic),
+            OpArgKind::Label => format!("Self::{} {{ .. }}", def.mnemonic),
+            OpArgKind::Arg => format!("Self::{} {{ .. }}", def.mnemonic),
+        };
+        let expr = match &def.stack_effect {
+            StackEffect::Fixed(term) => term.render(),
+            StackEffect::PerEdge(not_taken, taken) => {
+                format!("if jump {{ {} }} else {{ {} }}", taken.render(), not_taken.render())
+            }
+        };
+        writeln!(out, "            {pattern} => {expr},").unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(
+        out,
+        "    /// How many values this instruction pops off the stack with `oparg`,\n    /// on the fall-through edge (`jump == false`) or the taken-jump edge\n    /// (`jump == true`)."
+    )
+    .unwrap();
+    writeln!(out, "    pub const fn num_popped(self, oparg: u32, jump: bool) -> i32 {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for def in defs {
+        let pattern = match def.oparg {
+            OpArgKind::None => format!("Self::{}", def.mnemonic),
+            OpArgKind::Label | OpArgKind::Arg => format!("Self::{} {{ .. }}", def.mnemonic),
+        };
+        let expr = match &def.popped {
+            StackEffect::Fixed(term) => term.render(),
+            StackEffect::PerEdge(not_taken, taken) => {
+                format!("if jump {{ {} }} else {{ {} }}", taken.render(), not_taken.render())
+            }
+        };
+        writeln!(out, "            {pattern} => {expr},").unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(
+        out,
+        "    /// How many values this instruction pushes onto the stack with `oparg`,\n    /// on the given edge -- derived as `stack_effect + num_popped` since net ==\n    /// pushed - popped."
+    )
+    .unwrap();
+    writeln!(out, "    pub const fn num_pushed(self, oparg: u32, jump: bool) -> i32 {{").unwrap();
+    writeln!(
+        out,
+        "        self.stack_effect(oparg, jump) + self.num_popped(oparg, jump)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(
+        out,
+        "    /// Alias for [`Instruction::stack_effect`], named to match the\n    /// `num_popped`/`num_pushed` pair it complements."
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    pub const fn net_stack_effect(self, oparg: u32, jump: bool) -> i32 {{"
+    )
+    .unwrap();
+    writeln!(out, "        self.stack_effect(oparg, jump)").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "    /// This instruction's mnemonic, e.g. `\"LoadConst\"`.").unwrap();
+    writeln!(out, "    pub const fn opname(self) -> &'static str {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for def in defs {
+        let pattern = match def.oparg {
+            OpArgKind::None => format!("Self::{}", def.mnemonic),
+            OpArgKind::Label | OpArgKind::Arg => format!("Self::{} {{ .. }}", def.mnemonic),
+        };
+        writeln!(out, "            {pattern} => {name:?},", name = def.mnemonic).unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "    /// The instruction named `mnemonic`; the inverse of [`Instruction::opname`].").unwrap();
+    writeln!(out, "    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {{").unwrap();
+    writeln!(out, "        match mnemonic {{").unwrap();
+    for def in defs {
+        writeln!(out, "            {name:?} => Some(Self::{name}),", name = def.mnemonic).unwrap();
+    }
+    writeln!(out, "            _ => None,").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(
+        out,
+        "    /// Whether CPython specializes this mnemonic's call sites (see\n    /// `bytecode/specialize.rs`)."
+    )
+    .unwrap();
+    writeln!(out, "    pub const fn is_adaptive(self) -> bool {{").unwrap();
+    writeln!(out, "        match self {{").unwrap();
+    for def in defs {
+        let pattern = match def.oparg {
+            OpArgKind::None => format!("Self::{}", def.mnemonic),
+            OpArgKind::Label | OpArgKind::Arg => format!("Self::{} {{ .. }}", def.mnemonic),
+        };
+        writeln!(out, "            {pattern} => {adaptive},", adaptive = def.adaptive).unwrap();
+    }
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+
+    out
+}
@@ -1,17 +1,64 @@
 //! Implement python as a virtual machine with bytecode. This module
 //! implements bytecode structure.
 
+mod arena;
+mod assembler;
+mod cache;
+mod category;
+mod cfg;
 mod code;
 mod constant;
+mod disassemble;
+mod dot;
+mod exceptions;
+mod generated;
 mod instructions;
+mod intern;
+mod kind;
+mod liveness;
+mod monitoring;
 mod oparg;
 mod oparg_types;
+mod operation;
+mod optimizer;
+mod pool;
+mod positions;
+mod specialize;
+mod uop;
+mod verify;
+mod wordcode;
 
+pub use arena::{ArenaBag, ArenaConstant};
+pub use assembler::{AssembleError, assemble, parse_asm};
+pub use cache::{CacheError, read_cached_code, write_cached_code};
+pub use category::InstructionKind;
+pub use cfg::{BasicBlock, Cfg, Dominators, dead_blocks};
+pub use disassemble::{ArgVal, DisInstruction};
+pub use dot::to_dot;
+pub use exceptions::{ExceptionTableEntry, MalformedExceptionTable, decode_exception_table};
+pub use optimizer::optimize;
+pub use pool::ConstantPoolBuilder;
+pub use positions::SourcePosition;
+pub use specialize::{AdaptiveCounter, QuickenSite, SpecializeDecision, Specializer};
+pub use uop::{MicroOp, project_trace};
+pub use verify::VerifyError;
+pub use wordcode::{WordcodeError, decode, encode};
 pub use code::{CodeFlags, CodeObject, CodeUnit, CodeUnits};
 pub use constant::{AsBag, BorrowedConstant, Constant, ConstantBag, ConstantData};
 pub use instructions::{PseudoInstruction, RealInstruction};
+pub use intern::{InternBag, Sym, SymConstant, resolve};
+pub use kind::{
+    BinaryOperatorOparg, Buffer, CompareOperandOparg, CompareOperatorOparg, Encodable,
+    FormatValueConversion, FormatValueSpec, IntrinsicFunction1Oparg, IntrinsicFunction2Oparg,
+    MakeFunctionFlags, OpargFamily, OpargFamilyMember, ResumeOparg, ResumeOpargMask,
+};
+pub use liveness::{Liveness, analyze};
+pub use monitoring::{
+    EventSet, LocalDisable, MAX_TOOLS, ToolId, ToolRegistry, base_instruction, instrumented_form,
+};
 pub use oparg::{AnyOparg, Oparg, OpargByte, OpargState, OpargType};
 pub use oparg_types::*;
+pub use operation::{Operation, operation_for};
 
 /*
 use itertools::Itertools;
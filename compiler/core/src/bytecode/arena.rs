@@ -0,0 +1,112 @@
+//! An arena-backed [`ConstantBag`] that replaces per-node heap allocations
+//! with bump allocation out of a single [`bumpalo::Bump`].
+//!
+//! [`BasicBag`] does one heap allocation per tuple, per code object and per
+//! name; compiling a large module produces thousands of these, each with its
+//! own `Drop` to run later. A bump arena allocates without ever freeing
+//! individually -- the whole arena (and every node in it) is reclaimed in one
+//! shot when the compile unit's [`Bump`] is dropped, which matches the
+//! compiler's own lifetime exactly and skips the per-node traversal.
+//!
+//! [`ArenaBag`]'s `Constant` is [`ArenaConstant`], a `&'a`-lifetime handle
+//! into the arena rather than an owned, heap-allocated value. Once a
+//! [`CodeObject<ArenaConstant<'a>>`] needs to outlive the arena (e.g. to be
+//! cached or sent elsewhere), [`CodeObject::map_clone_bag`] with [`BasicBag`]
+//! walks it back into owned [`ConstantData`], same as any other `ConstantBag`
+//! boundary crossing.
+
+use bumpalo::Bump;
+use malachite_bigint::BigInt;
+use num_complex::Complex64;
+
+use super::{BasicBag, BorrowedConstant, CodeObject, Constant, ConstantBag};
+
+/// A constant whose payload lives in an [`ArenaBag`]'s [`Bump`], valid for
+/// as long as the arena itself is.
+pub enum ArenaConstant<'a> {
+    Integer(&'a BigInt),
+    Float(f64),
+    Complex(Complex64),
+    Boolean(bool),
+    Str(&'a str),
+    Bytes(&'a [u8]),
+    Code(&'a CodeObject<ArenaConstant<'a>>),
+    Tuple(&'a [ArenaConstant<'a>]),
+    None,
+    Ellipsis,
+}
+
+impl<'a> Constant for ArenaConstant<'a> {
+    type Name = &'a str;
+
+    fn borrow_constant(&self) -> BorrowedConstant<'_, Self> {
+        match self {
+            Self::Integer(value) => BorrowedConstant::Integer { value },
+            Self::Float(value) => BorrowedConstant::Float { value: *value },
+            Self::Complex(value) => BorrowedConstant::Complex { value: *value },
+            Self::Boolean(value) => BorrowedConstant::Boolean { value: *value },
+            Self::Str(value) => BorrowedConstant::Str { value: (*value).into() },
+            Self::Bytes(value) => BorrowedConstant::Bytes { value },
+            Self::Code(code) => BorrowedConstant::Code { code },
+            Self::Tuple(elements) => BorrowedConstant::Tuple { elements },
+            Self::None => BorrowedConstant::None,
+            Self::Ellipsis => BorrowedConstant::Ellipsis,
+        }
+    }
+}
+
+/// A [`ConstantBag`] that allocates every constant it builds out of `'a`'s
+/// [`Bump`] instead of the global heap.
+#[derive(Clone, Copy)]
+pub struct ArenaBag<'a> {
+    arena: &'a Bump,
+}
+
+impl<'a> ArenaBag<'a> {
+    pub fn new(arena: &'a Bump) -> Self {
+        Self { arena }
+    }
+}
+
+impl<'a> ConstantBag for ArenaBag<'a> {
+    type Constant = ArenaConstant<'a>;
+
+    fn make_constant<C: Constant>(&self, constant: BorrowedConstant<'_, C>) -> Self::Constant {
+        match constant {
+            BorrowedConstant::Integer { value } => ArenaConstant::Integer(self.arena.alloc(value.clone())),
+            BorrowedConstant::Float { value } => ArenaConstant::Float(value),
+            BorrowedConstant::Complex { value } => ArenaConstant::Complex(value),
+            BorrowedConstant::Boolean { value } => ArenaConstant::Boolean(value),
+            BorrowedConstant::Str { value } => {
+                ArenaConstant::Str(self.arena.alloc_str(value.as_str().unwrap_or_default()))
+            }
+            BorrowedConstant::Bytes { value } => ArenaConstant::Bytes(self.arena.alloc_slice_copy(value)),
+            BorrowedConstant::Code { code } => {
+                ArenaConstant::Code(self.arena.alloc(code.map_clone_bag(self)))
+            }
+            BorrowedConstant::Tuple { elements } => ArenaConstant::Tuple(
+                self.arena
+                    .alloc_slice_fill_iter(elements.iter().map(|c| self.make_constant(c.borrow_constant()))),
+            ),
+            BorrowedConstant::None => ArenaConstant::None,
+            BorrowedConstant::Ellipsis => ArenaConstant::Ellipsis,
+        }
+    }
+
+    fn make_int(&self, value: BigInt) -> Self::Constant {
+        ArenaConstant::Integer(self.arena.alloc(value))
+    }
+
+    fn make_tuple(&self, elements: impl Iterator<Item = Self::Constant>) -> Self::Constant {
+        let elements: Vec<_> = elements.collect();
+        ArenaConstant::Tuple(self.arena.alloc_slice_fill_iter(elements))
+    }
+
+    fn make_code(&self, code: CodeObject<Self::Constant>) -> Self::Constant {
+        ArenaConstant::Code(self.arena.alloc(code))
+    }
+
+    fn make_name(&self, name: &str) -> <Self::Constant as Constant>::Name {
+        self.arena.alloc_str(name)
+    }
+}
@@ -0,0 +1,516 @@
+//! Textual bytecode assembler: the inverse of [`CodeObject::display_inner`].
+//!
+//! The disassembly listing produced by [`CodeObject::display_expand_code_objects`]
+//! is human-readable but one-way. This module parses that listing back into a
+//! [`CodeObject<ConstantData>`], so bytecode can be patched by hand, checked in as
+//! a text fixture, or produced by external tooling and reassembled.
+//!
+//! Jump arguments may be written as symbolic labels. Because an instruction's
+//! encoded size depends on the magnitude of its oparg (extra `ExtendedArg`
+//! prefixes widen it), label offsets and instruction sizes are mutually
+//! dependent. We therefore resolve them to a fixpoint: assume minimal sizes,
+//! compute offsets, re-encode, and repeat until no instruction changes size.
+//!
+//! [`assemble`] still needs a `template` for metadata the instruction listing
+//! alone doesn't carry. [`parse_asm`] lifts that requirement: a `.directive`
+//! header (see its doc comment) declares the name/arg-count/symbol-table
+//! metadata and constant pool -- including nested `code` constants -- textually,
+//! so a listing produced by [`CodeObject::display_expand_code_objects`] can be
+//! reassembled with nothing but the text itself. It also recomputes
+//! `max_stackdepth` from the assembled instructions rather than trusting a
+//! stale value carried in from the template.
+
+use super::{Cfg, CodeObject, CodeUnit, ConstantData, Instruction, Label, Oparg, OpArgState};
+use malachite_bigint::BigInt;
+use std::collections::HashMap;
+
+/// A failure encountered while assembling a textual listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssembleError {
+    /// A line did not match `<label?> <opcode> <arg?>`.
+    MalformedLine(usize),
+    /// An opcode mnemonic that does not name any [`Instruction`].
+    UnknownOpcode(String),
+    /// A jump referenced a label that was never defined.
+    UndefinedLabel(String),
+    /// A constant literal could not be parsed back into [`ConstantData`].
+    BadConstant(String),
+    /// A `.directive` line in a [`parse_asm`] header wasn't recognized, or
+    /// was malformed for the directive it named.
+    BadDirective(String),
+    /// A `.const code` block had no matching `.endcode`.
+    UnterminatedCodeBlock,
+    /// The header had no `.code` line marking where the instruction body
+    /// begins.
+    MissingCodeDirective,
+}
+
+/// One parsed instruction, before label offsets are known.
+struct ParsedInstr {
+    instr: Instruction,
+    /// `Some(name)` for a symbolic jump target, `None` for a literal oparg.
+    label_ref: Option<String>,
+    arg: Oparg,
+}
+
+/// Parse a textual listing into an instruction stream plus a label table, then
+/// re-encode it into a [`CodeObject`]. `template` supplies the metadata (name,
+/// flags, symbol tables) that the listing alone does not carry.
+pub fn assemble(
+    listing: &str,
+    template: CodeObject<ConstantData>,
+) -> Result<CodeObject<ConstantData>, AssembleError> {
+    let mut parsed: Vec<ParsedInstr> = Vec::new();
+    // name -> index into `parsed` of the instruction it marks.
+    let mut labels: HashMap<String, usize> = HashMap::new();
+
+    for (lineno, raw) in listing.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        // A leading `label:` defines a jump target for the next instruction.
+        let rest = if let Some((name, rest)) = line.split_once(':') {
+            if !rest.trim_start().is_empty() && !name.contains(char::is_whitespace) {
+                labels.insert(name.trim().to_owned(), parsed.len());
+                rest.trim_start()
+            } else {
+                line
+            }
+        } else {
+            line
+        };
+
+        let mut tokens = rest.splitn(2, char::is_whitespace);
+        let mnemonic = tokens.next().ok_or(AssembleError::MalformedLine(lineno))?;
+        let operand = tokens.next().map(str::trim);
+
+        let instr = Instruction::from_mnemonic(mnemonic)
+            .ok_or_else(|| AssembleError::UnknownOpcode(mnemonic.to_owned()))?;
+
+        let (label_ref, arg) = match operand {
+            Some(op) if instr.label_arg().is_some() && !is_numeric(op) => {
+                (Some(op.to_owned()), Oparg::NULL)
+            }
+            Some(op) => (
+                None,
+                Oparg::new(op.parse().map_err(|_| AssembleError::MalformedLine(lineno))?),
+            ),
+            None => (None, Oparg::NULL),
+        };
+        parsed.push(ParsedInstr {
+            instr,
+            label_ref,
+            arg,
+        });
+    }
+
+    let offsets = resolve_offsets(&parsed, &labels)?;
+    let instructions = encode(&parsed, &labels, &offsets)?;
+
+    Ok(CodeObject {
+        instructions: instructions.into_boxed_slice(),
+        ..template
+    })
+}
+
+fn is_numeric(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Compute the code-unit offset of each parsed instruction, iterating to a
+/// fixpoint because an oparg that crosses a byte boundary adds `ExtendedArg`
+/// prefixes and shifts every later offset.
+fn resolve_offsets(
+    parsed: &[ParsedInstr],
+    labels: &HashMap<String, usize>,
+) -> Result<Vec<u32>, AssembleError> {
+    let mut offsets = vec![0u32; parsed.len()];
+    loop {
+        let mut changed = false;
+        let mut offset = 0u32;
+        for (i, p) in parsed.iter().enumerate() {
+            if offsets[i] != offset {
+                offsets[i] = offset;
+                changed = true;
+            }
+            let arg = effective_arg(p, labels, &offsets)?;
+            offset += arg.instr_size() as u32;
+        }
+        if !changed {
+            return Ok(offsets);
+        }
+    }
+}
+
+/// The oparg an instruction encodes with, resolving any symbolic label against
+/// the current offset estimate.
+fn effective_arg(
+    p: &ParsedInstr,
+    labels: &HashMap<String, usize>,
+    offsets: &[u32],
+) -> Result<Oparg, AssembleError> {
+    match &p.label_ref {
+        Some(name) => {
+            let target = *labels
+                .get(name)
+                .ok_or_else(|| AssembleError::UndefinedLabel(name.clone()))?;
+            Ok(Oparg::new(offsets[target]))
+        }
+        None => Ok(p.arg),
+    }
+}
+
+/// Emit the final `CodeUnit` stream, regenerating `ExtendedArg` prefixes via
+/// [`Oparg::split`].
+fn encode(
+    parsed: &[ParsedInstr],
+    labels: &HashMap<String, usize>,
+    offsets: &[u32],
+) -> Result<Vec<CodeUnit>, AssembleError> {
+    let mut out = Vec::with_capacity(parsed.len());
+    for p in parsed {
+        let arg = effective_arg(p, labels, offsets)?;
+        let (ext, lo) = arg.split();
+        for byte in ext {
+            out.push(CodeUnit::new(Instruction::ExtendedArg, byte));
+        }
+        out.push(CodeUnit::new(p.instr, lo));
+    }
+    Ok(out)
+}
+
+/// Resolve a label name to its defined offset, for callers inspecting a parse.
+pub fn label_offset(
+    labels: &HashMap<String, usize>,
+    offsets: &[u32],
+    name: &str,
+) -> Option<Label> {
+    labels.get(name).map(|&i| Label(offsets[i]))
+}
+
+/// Parse a fully self-contained textual listing -- a `.directive` header
+/// plus the instruction body [`assemble`] already knows how to parse -- into
+/// a complete [`CodeObject`], with no external template needed.
+///
+/// # Header grammar
+///
+/// One directive per line, in any order, terminated by a bare `.code` line
+/// (everything after it is the instruction listing body):
+///
+/// ```text
+/// .name <ident>                 (default: "<module>")
+/// .qualname <ident>              (default: same as .name)
+/// .source_path <ident>           (default: "<string>")
+/// .arg_count <n>
+/// .posonlyarg_count <n>
+/// .kwonlyarg_count <n>
+/// .flags <bits>                  (default: 0)
+/// .names <a>,<b>,...
+/// .varnames <a>,<b>,...
+/// .cellvars <a>,<b>,...
+/// .freevars <a>,<b>,...
+/// .const none
+/// .const ellipsis
+/// .const bool <true|false>
+/// .const int <literal>
+/// .const float <literal>
+/// .const str <quoted string>
+/// .const code
+///     <nested .directive header + .code body>
+/// .endcode
+/// .code
+/// <instruction listing>
+/// ```
+///
+/// Each `.const` directive appends one entry to the constant pool, in the
+/// order written; a `.const code` block recurses into [`parse_asm`] on its
+/// own nested text.
+pub fn parse_asm(text: &str) -> Result<CodeObject<ConstantData>, AssembleError> {
+    let mut lines = text.lines().peekable();
+    let mut header = Header::default();
+    let mut saw_code_directive = false;
+
+    while let Some(raw) = lines.peek().copied() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            lines.next();
+            continue;
+        }
+        if line == ".code" {
+            lines.next();
+            saw_code_directive = true;
+            break;
+        }
+        if !line.starts_with('.') {
+            return Err(AssembleError::BadDirective(line.to_owned()));
+        }
+        lines.next();
+        header.apply(line, &mut lines)?;
+    }
+    if !saw_code_directive {
+        return Err(AssembleError::MissingCodeDirective);
+    }
+
+    let body: String = lines.collect::<Vec<_>>().join("\n");
+    let template = header.into_template()?;
+    let assembled = assemble(&body, template)?;
+    let max_stackdepth = compute_max_stackdepth(&assembled);
+    Ok(CodeObject {
+        max_stackdepth,
+        ..assembled
+    })
+}
+
+/// Accumulates the metadata a [`parse_asm`] header declares, defaulting
+/// anything left unspecified the same way an empty module's code object
+/// would.
+struct Header {
+    name: String,
+    qualname: Option<String>,
+    source_path: String,
+    arg_count: u32,
+    posonlyarg_count: u32,
+    kwonlyarg_count: u32,
+    flags: u16,
+    names: Vec<String>,
+    varnames: Vec<String>,
+    cellvars: Vec<String>,
+    freevars: Vec<String>,
+    constants: Vec<ConstantData>,
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Self {
+            name: "<module>".to_owned(),
+            qualname: None,
+            source_path: "<string>".to_owned(),
+            arg_count: 0,
+            posonlyarg_count: 0,
+            kwonlyarg_count: 0,
+            flags: 0,
+            names: Vec::new(),
+            varnames: Vec::new(),
+            cellvars: Vec::new(),
+            freevars: Vec::new(),
+            constants: Vec::new(),
+        }
+    }
+}
+
+fn csv_list(rest: &str) -> Vec<String> {
+    rest.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+impl Header {
+    /// Apply one already-trimmed, non-`.code` directive line, consuming
+    /// further lines from `lines` for a multi-line `.const code` block.
+    fn apply<'a, I: Iterator<Item = &'a str>>(
+        &mut self,
+        line: &str,
+        lines: &mut std::iter::Peekable<I>,
+    ) -> Result<(), AssembleError> {
+        let mut tokens = line.splitn(2, char::is_whitespace);
+        let directive = tokens.next().unwrap_or_default();
+        let rest = tokens.next().map(str::trim).unwrap_or_default();
+
+        match directive {
+            ".name" => self.name = rest.to_owned(),
+            ".qualname" => self.qualname = Some(rest.to_owned()),
+            ".source_path" => self.source_path = rest.to_owned(),
+            ".arg_count" => self.arg_count = parse_u32(rest)?,
+            ".posonlyarg_count" => self.posonlyarg_count = parse_u32(rest)?,
+            ".kwonlyarg_count" => self.kwonlyarg_count = parse_u32(rest)?,
+            ".flags" => self.flags = parse_u32(rest)? as u16,
+            ".names" => self.names = csv_list(rest),
+            ".varnames" => self.varnames = csv_list(rest),
+            ".cellvars" => self.cellvars = csv_list(rest),
+            ".freevars" => self.freevars = csv_list(rest),
+            ".const" => self.constants.push(parse_const(rest, lines)?),
+            other => return Err(AssembleError::BadDirective(other.to_owned())),
+        }
+        Ok(())
+    }
+
+    fn into_template(self) -> Result<CodeObject<ConstantData>, AssembleError> {
+        Ok(CodeObject {
+            instructions: Vec::new().into_boxed_slice(),
+            locations: Vec::new().into_boxed_slice(),
+            flags: super::CodeFlags::from_bits_truncate(self.flags),
+            posonlyarg_count: self.posonlyarg_count,
+            arg_count: self.arg_count,
+            kwonlyarg_count: self.kwonlyarg_count,
+            source_path: self.source_path,
+            first_line_number: None,
+            max_stackdepth: 0,
+            obj_name: self.name.clone(),
+            qualname: self.qualname.unwrap_or(self.name),
+            cell2arg: None,
+            constants: self.constants.into_boxed_slice(),
+            names: self.names.into_boxed_slice(),
+            varnames: self.varnames.into_boxed_slice(),
+            cellvars: self.cellvars.into_boxed_slice(),
+            freevars: self.freevars.into_boxed_slice(),
+            linetable: Vec::new().into_boxed_slice(),
+            exceptiontable: Vec::new().into_boxed_slice(),
+        })
+    }
+}
+
+fn parse_u32(s: &str) -> Result<u32, AssembleError> {
+    if let Some(hex) = s.strip_prefix("0x") {
+        u32::from_str_radix(hex, 16).map_err(|_| AssembleError::BadDirective(s.to_owned()))
+    } else {
+        s.parse().map_err(|_| AssembleError::BadDirective(s.to_owned()))
+    }
+}
+
+fn unquote(s: &str) -> Result<String, AssembleError> {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .map(str::to_owned)
+        .ok_or_else(|| AssembleError::BadConstant(s.to_owned()))
+}
+
+/// Parse one `.const <kind> <value>` directive's payload, consuming the
+/// nested block from `lines` when `kind` is `code`.
+fn parse_const<'a, I: Iterator<Item = &'a str>>(
+    rest: &str,
+    lines: &mut std::iter::Peekable<I>,
+) -> Result<ConstantData, AssembleError> {
+    let mut tokens = rest.splitn(2, char::is_whitespace);
+    let kind = tokens.next().unwrap_or_default();
+    let value = tokens.next().map(str::trim).unwrap_or_default();
+
+    Ok(match kind {
+        "none" => ConstantData::None,
+        "ellipsis" => ConstantData::Ellipsis,
+        "bool" => ConstantData::Boolean {
+            value: value == "true",
+        },
+        "int" => ConstantData::Integer {
+            value: value
+                .parse::<BigInt>()
+                .map_err(|_| AssembleError::BadConstant(value.to_owned()))?,
+        },
+        "float" => ConstantData::Float {
+            value: value
+                .parse()
+                .map_err(|_| AssembleError::BadConstant(value.to_owned()))?,
+        },
+        "str" => ConstantData::Str {
+            value: unquote(value)?.into(),
+        },
+        "code" => {
+            let mut nested = String::new();
+            loop {
+                let line = lines.next().ok_or(AssembleError::UnterminatedCodeBlock)?;
+                if line.trim() == ".endcode" {
+                    break;
+                }
+                nested.push_str(line);
+                nested.push('\n');
+            }
+            ConstantData::Code {
+                code: Box::new(parse_asm(&nested)?),
+            }
+        }
+        other => return Err(AssembleError::BadConstant(other.to_owned())),
+    })
+}
+
+/// Recompute `max_stackdepth` for an already-assembled code object, via a
+/// worklist over [`Cfg`] blocks: every block is entered with the stack depth
+/// its predecessors leave it at (a fixpoint, since a loop's back-edge can
+/// only be resolved once the whole cycle's been walked once), and every
+/// instruction inside it advances the running depth by
+/// [`Instruction::stack_effect`]. Only a block's last instruction can differ
+/// by edge (everything earlier in the block is, by construction, not a jump
+/// -- see `cfg.rs`), so the fall-through and taken-branch depths are tracked
+/// separately only there.
+fn compute_max_stackdepth(code: &CodeObject<ConstantData>) -> u32 {
+    struct Decoded {
+        offset: u32,
+        instr: Instruction,
+        arg: u32,
+    }
+    let mut decoded = Vec::new();
+    let mut state = OpArgState::default();
+    let mut seq_start = 0u32;
+    for (idx, &unit) in code.instructions.iter().enumerate() {
+        let idx = idx as u32;
+        let (instr, arg) = state.get(unit);
+        if instr == Instruction::ExtendedArg {
+            continue;
+        }
+        decoded.push(Decoded {
+            offset: seq_start,
+            instr,
+            arg: arg.as_u32(),
+        });
+        seq_start = idx + 1;
+    }
+
+    let cfg = Cfg::build(code);
+    let members: Vec<Vec<usize>> = cfg
+        .blocks()
+        .iter()
+        .map(|b| {
+            decoded
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| d.offset >= b.start.0 && d.offset < b.end.0)
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect();
+
+    let mut depth_in: Vec<Option<i64>> = vec![None; cfg.blocks().len()];
+    depth_in[0] = Some(0);
+    let mut max_depth = 0i64;
+    let mut queue = vec![0usize];
+    while let Some(b) = queue.pop() {
+        let Some(mut depth) = depth_in[b] else { continue };
+        max_depth = max_depth.max(depth);
+
+        let items = &members[b];
+        for &i in items.iter().take(items.len().saturating_sub(1)) {
+            let d = &decoded[i];
+            depth += i64::from(d.instr.stack_effect(d.arg, false));
+            max_depth = max_depth.max(depth);
+        }
+
+        let last_depth = |jump: bool| -> i64 {
+            match items.last() {
+                Some(&i) => {
+                    let d = &decoded[i];
+                    depth + i64::from(d.instr.stack_effect(d.arg, jump))
+                }
+                None => depth,
+            }
+        };
+
+        for (edge_idx, &succ) in cfg.successors(b).iter().enumerate() {
+            // The CFG records the fall-through successor first (when one
+            // exists) and the jump target second -- see `Cfg::build`.
+            let has_fallthrough = items
+                .last()
+                .map(|&i| !matches!(decoded[i].instr, Instruction::Jump))
+                .unwrap_or(true);
+            let jump = edge_idx > 0 || !has_fallthrough;
+            let out_depth = last_depth(jump);
+            max_depth = max_depth.max(out_depth);
+            if depth_in[succ] != Some(out_depth) {
+                depth_in[succ] = Some(out_depth);
+                queue.push(succ);
+            }
+        }
+    }
+
+    max_depth.max(0) as u32
+}
@@ -0,0 +1,422 @@
+//! A compact, versioned binary cache format for [`ConstantData`]/[`CodeObject`],
+//! distinct from the CPython-compatible `.pyc` codec in
+//! [`crate::marshal`]: where that module mirrors CPython's on-disk layout
+//! byte-for-byte (fixed 4-byte lengths, CPython's own tag alphabet) so a real
+//! `.pyc` can round-trip, this one is free to optimize purely for RustPython's
+//! own compile-then-cache path -- LEB128 varints for every length/oparg
+//! instead of fixed 4-byte fields, and a structural (not pointer) identity
+//! back-reference table so repeated tuples and shared child code objects are
+//! written once.
+//!
+//! [`ConstantData::Code`]'s [`PartialEq`] is pointer-based (two structurally
+//! identical but separately-compiled code objects don't compare equal), which
+//! is exactly wrong for a cache: two `LoadConst` sites that happen to embed
+//! the same nested code byte-for-byte should still share one cache entry.
+//! [`CacheWriter`] therefore keys its back-reference table off the *encoded
+//! bytes* of each constant rather than off [`ConstantData`]'s own `Eq`, which
+//! sidesteps that pointer-identity quirk entirely.
+
+use std::collections::HashMap;
+
+use super::{CodeFlags, CodeObject, ConstantData};
+use malachite_bigint::{BigInt, Sign};
+use num_complex::Complex64;
+use rustpython_wtf8::Wtf8Buf;
+
+/// Magic bytes plus format version, checked before any payload is trusted.
+const MAGIC: &[u8; 4] = b"RPYC";
+const VERSION: u8 = 1;
+
+/// A failure while reading or writing the cache format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheError {
+    /// The stream ended before a complete value could be read. Also returned
+    /// for a code unit whose opcode or oparg doesn't fit a byte, or whose
+    /// opcode byte names no [`Instruction`](super::Instruction).
+    Truncated,
+    /// The leading magic/version header didn't match.
+    BadHeader,
+    /// A tag byte did not name any constant type this format understands.
+    InvalidTag(u8),
+    /// A varint back-reference pointed outside the ref table built so far.
+    BadBackref(u32),
+}
+
+const TAG_NONE: u8 = 0;
+const TAG_ELLIPSIS: u8 = 1;
+const TAG_BOOL: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_FLOAT: u8 = 4;
+const TAG_COMPLEX: u8 = 5;
+const TAG_STR: u8 = 6;
+const TAG_BYTES: u8 = 7;
+const TAG_TUPLE: u8 = 8;
+const TAG_CODE: u8 = 9;
+/// A back-reference to an already-written constant, by ref-table index
+/// instead of re-encoding it.
+const TAG_REF: u8 = 10;
+
+/// Writes the cache format, deduping repeated constants into a
+/// back-reference table keyed by their own encoded bytes (structural
+/// identity), so a shared child [`CodeObject`] or a repeated tuple literal is
+/// only ever serialized once.
+pub struct CacheWriter {
+    out: Vec<u8>,
+    seen: HashMap<Vec<u8>, u32>,
+}
+
+impl CacheWriter {
+    pub fn new() -> Self {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        Self {
+            out,
+            seen: HashMap::new(),
+        }
+    }
+
+    fn write_uvarint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.out.push(byte);
+                return;
+            }
+            self.out.push(byte | 0x80);
+        }
+    }
+
+    fn write_bytes(&mut self, value: &[u8]) {
+        self.write_uvarint(value.len() as u64);
+        self.out.extend_from_slice(value);
+    }
+
+    /// Write `constant`, emitting a [`TAG_REF`] instead of a duplicate when an
+    /// identical encoding has already been written.
+    pub fn write_constant(&mut self, constant: &ConstantData) {
+        let mut body = Vec::new();
+        let mut scratch = CacheWriter {
+            out: std::mem::take(&mut body),
+            seen: std::mem::take(&mut self.seen),
+        };
+        scratch.encode_body(constant);
+        body = scratch.out;
+        self.seen = scratch.seen;
+
+        if let Some(&index) = self.seen.get(&body) {
+            self.out.push(TAG_REF);
+            self.write_uvarint(u64::from(index));
+            return;
+        }
+        let index = self.seen.len() as u32;
+        self.seen.insert(body.clone(), index);
+        self.out.extend_from_slice(&body);
+    }
+
+    fn encode_body(&mut self, constant: &ConstantData) {
+        match constant {
+            ConstantData::None => self.out.push(TAG_NONE),
+            ConstantData::Ellipsis => self.out.push(TAG_ELLIPSIS),
+            ConstantData::Boolean { value } => {
+                self.out.push(TAG_BOOL);
+                self.out.push(*value as u8);
+            }
+            ConstantData::Integer { value } => {
+                self.out.push(TAG_INT);
+                let (sign, magnitude) = value.to_bytes_le();
+                self.out.push(match sign {
+                    Sign::Minus => 1,
+                    _ => 0,
+                });
+                self.write_bytes(&magnitude);
+            }
+            ConstantData::Float { value } => {
+                self.out.push(TAG_FLOAT);
+                self.out.extend_from_slice(&value.to_bits().to_le_bytes());
+            }
+            ConstantData::Complex { value } => {
+                self.out.push(TAG_COMPLEX);
+                self.out.extend_from_slice(&value.re.to_bits().to_le_bytes());
+                self.out.extend_from_slice(&value.im.to_bits().to_le_bytes());
+            }
+            ConstantData::Str { value } => {
+                self.out.push(TAG_STR);
+                self.write_bytes(value.as_bytes());
+            }
+            ConstantData::Bytes { value } => {
+                self.out.push(TAG_BYTES);
+                self.write_bytes(value);
+            }
+            ConstantData::Tuple { elements } => {
+                self.out.push(TAG_TUPLE);
+                self.write_uvarint(elements.len() as u64);
+                for element in elements {
+                    self.write_constant(element);
+                }
+            }
+            ConstantData::Code { code } => {
+                self.out.push(TAG_CODE);
+                self.write_code(code);
+            }
+        }
+    }
+
+    fn write_code(&mut self, code: &CodeObject<ConstantData>) {
+        self.write_uvarint(code.instructions.len() as u64);
+        for unit in &*code.instructions {
+            self.write_uvarint(u64::from(u8::from(unit.op)));
+            self.write_uvarint(u64::from(*unit.arg));
+        }
+        self.write_uvarint(code.posonlyarg_count as u64);
+        self.write_uvarint(code.arg_count as u64);
+        self.write_uvarint(code.kwonlyarg_count as u64);
+        self.write_uvarint(code.max_stackdepth as u64);
+        self.write_uvarint(code.flags.bits() as u64);
+
+        self.write_bytes(&code.obj_name);
+        self.write_bytes(&code.qualname);
+        self.write_bytes(&code.source_path);
+
+        self.write_uvarint(code.constants.len() as u64);
+        for constant in &*code.constants {
+            self.write_constant(constant);
+        }
+        self.write_name_list(&code.names);
+        self.write_name_list(&code.varnames);
+        self.write_name_list(&code.cellvars);
+        self.write_name_list(&code.freevars);
+
+        self.write_bytes(&code.linetable);
+        self.write_bytes(&code.exceptiontable);
+    }
+
+    fn write_name_list(&mut self, names: &[String]) {
+        self.write_uvarint(names.len() as u64);
+        for name in names {
+            self.write_bytes(name.as_bytes());
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.out
+    }
+}
+
+impl Default for CacheWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct CacheReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    refs: Vec<ConstantData>,
+}
+
+impl<'a> CacheReader<'a> {
+    fn u8(&mut self) -> Result<u8, CacheError> {
+        let byte = *self.data.get(self.pos).ok_or(CacheError::Truncated)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CacheError> {
+        let end = self.pos.checked_add(n).ok_or(CacheError::Truncated)?;
+        let slice = self.data.get(self.pos..end).ok_or(CacheError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_uvarint(&mut self) -> Result<u64, CacheError> {
+        let mut result = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            result |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>, CacheError> {
+        let len = self.read_uvarint()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn read_constant(&mut self) -> Result<ConstantData, CacheError> {
+        let tag = self.u8()?;
+        let value = match tag {
+            TAG_REF => {
+                let index = self.read_uvarint()? as u32;
+                return self
+                    .refs
+                    .get(index as usize)
+                    .cloned()
+                    .ok_or(CacheError::BadBackref(index));
+            }
+            TAG_NONE => ConstantData::None,
+            TAG_ELLIPSIS => ConstantData::Ellipsis,
+            TAG_BOOL => ConstantData::Boolean {
+                value: self.u8()? != 0,
+            },
+            TAG_INT => {
+                let negative = self.u8()? != 0;
+                let magnitude = self.read_bytes()?;
+                let sign = if negative { Sign::Minus } else { Sign::Plus };
+                ConstantData::Integer {
+                    value: BigInt::from_bytes_le(sign, &magnitude),
+                }
+            }
+            TAG_FLOAT => {
+                let bits = u64::from_le_bytes(self.take(8)?.try_into().unwrap());
+                ConstantData::Float {
+                    value: f64::from_bits(bits),
+                }
+            }
+            TAG_COMPLEX => {
+                let re = f64::from_bits(u64::from_le_bytes(self.take(8)?.try_into().unwrap()));
+                let im = f64::from_bits(u64::from_le_bytes(self.take(8)?.try_into().unwrap()));
+                ConstantData::Complex {
+                    value: Complex64::new(re, im),
+                }
+            }
+            TAG_STR => {
+                let raw = self.read_bytes()?;
+                ConstantData::Str {
+                    value: Wtf8Buf::from(String::from_utf8_lossy(&raw).into_owned()),
+                }
+            }
+            TAG_BYTES => ConstantData::Bytes {
+                value: self.read_bytes()?,
+            },
+            TAG_TUPLE => {
+                let len = self.read_uvarint()?;
+                let elements = (0..len).map(|_| self.read_constant()).collect::<Result<_, _>>()?;
+                ConstantData::Tuple { elements }
+            }
+            TAG_CODE => ConstantData::Code {
+                code: Box::new(self.read_code()?),
+            },
+            other => return Err(CacheError::InvalidTag(other)),
+        };
+        // Structural identity: every freshly-decoded value gets its own slot,
+        // matching the writer's one-slot-per-distinct-encoding scheme.
+        self.refs.push(value.clone());
+        Ok(value)
+    }
+
+    fn read_name_list(&mut self) -> Result<Box<[String]>, CacheError> {
+        let len = self.read_uvarint()?;
+        (0..len)
+            .map(|_| {
+                let raw = self.read_bytes()?;
+                Ok(String::from_utf8_lossy(&raw).into_owned())
+            })
+            .collect()
+    }
+
+    fn read_code(&mut self) -> Result<CodeObject<ConstantData>, CacheError> {
+        use super::{CodeUnit, OpArgByte};
+
+        let unit_count = self.read_uvarint()?;
+        let mut instructions = Vec::with_capacity(unit_count as usize);
+        for _ in 0..unit_count {
+            let op = u8::try_from(self.read_uvarint()?).map_err(|_| CacheError::Truncated)?;
+            let arg = u8::try_from(self.read_uvarint()?).map_err(|_| CacheError::Truncated)?;
+            let op = super::Instruction::try_from(op).map_err(|_| CacheError::Truncated)?;
+            instructions.push(CodeUnit::new(op, OpArgByte(arg)));
+        }
+
+        let posonlyarg_count = self.read_uvarint()? as u32;
+        let arg_count = self.read_uvarint()? as u32;
+        let kwonlyarg_count = self.read_uvarint()? as u32;
+        let max_stackdepth = self.read_uvarint()? as u32;
+        let flags = CodeFlags::from_bits_truncate(self.read_uvarint()? as u16);
+
+        let obj_name = String::from_utf8_lossy(&self.read_bytes()?).into_owned();
+        let qualname = String::from_utf8_lossy(&self.read_bytes()?).into_owned();
+        let source_path = String::from_utf8_lossy(&self.read_bytes()?).into_owned();
+
+        let const_count = self.read_uvarint()?;
+        let constants = (0..const_count)
+            .map(|_| self.read_constant())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_boxed_slice();
+        let names = self.read_name_list()?;
+        let varnames = self.read_name_list()?;
+        let cellvars = self.read_name_list()?;
+        let freevars = self.read_name_list()?;
+
+        let linetable = self.read_bytes()?.into_boxed_slice();
+        let exceptiontable = self.read_bytes()?.into_boxed_slice();
+
+        Ok(CodeObject {
+            instructions: instructions.into_boxed_slice(),
+            locations: Vec::new().into_boxed_slice(),
+            flags,
+            posonlyarg_count,
+            arg_count,
+            kwonlyarg_count,
+            source_path,
+            first_line_number: None,
+            max_stackdepth,
+            obj_name,
+            qualname,
+            cell2arg: None,
+            constants,
+            names,
+            varnames,
+            cellvars,
+            freevars,
+            linetable,
+            exceptiontable,
+        })
+    }
+}
+
+impl ConstantData {
+    /// Serialize `self` into the cache format, appending to `out`.
+    pub fn marshal_to(&self, out: &mut Vec<u8>) {
+        let mut writer = CacheWriter {
+            out: std::mem::take(out),
+            seen: HashMap::new(),
+        };
+        writer.write_constant(self);
+        *out = writer.out;
+    }
+
+    /// The inverse of [`ConstantData::marshal_to`].
+    pub fn unmarshal_from(bytes: &[u8]) -> Result<Self, CacheError> {
+        CacheReader {
+            data: bytes,
+            pos: 0,
+            refs: Vec::new(),
+        }
+        .read_constant()
+    }
+}
+
+/// Serialize a [`CodeObject`] into the cache format, header included.
+pub fn write_cached_code(code: &CodeObject<ConstantData>) -> Vec<u8> {
+    let mut writer = CacheWriter::new();
+    writer.write_code(code);
+    writer.into_bytes()
+}
+
+/// Deserialize a [`CodeObject`] previously written by [`write_cached_code`].
+pub fn read_cached_code(data: &[u8]) -> Result<CodeObject<ConstantData>, CacheError> {
+    if data.len() < MAGIC.len() + 1 || &data[..MAGIC.len()] != MAGIC || data[MAGIC.len()] != VERSION {
+        return Err(CacheError::BadHeader);
+    }
+    CacheReader {
+        data,
+        pos: MAGIC.len() + 1,
+        refs: Vec::new(),
+    }
+    .read_code()
+}
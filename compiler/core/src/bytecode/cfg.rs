@@ -0,0 +1,290 @@
+//! Control-flow-graph construction over a decoded instruction stream, so
+//! peephole/dead-code/reachability passes can run before assembly.
+//!
+//! A new basic block starts at offset `0`, at every jump target, and
+//! immediately after any terminator (an unconditional jump, or a `Return*`/
+//! `Reraise`-style instruction with no fall-through) or any other
+//! instruction that carries a jump target. Edges are added for
+//! the fall-through successor (when one exists) and for the jump target
+//! (when the instruction has one). [`Cfg::dominators`] builds the
+//! immediate-dominator tree on top of that graph, for passes (like
+//! redundant-guard elimination) that need "is this definitely already
+//! true on every path here" rather than just reachability.
+//!
+//! This table's `Instruction` enum has no `SetupFinally`/`SetupWith`/
+//! `SetupCleanup` exception-block-setup mnemonics (those live only on the
+//! `PseudoInstruction` side of the tree's other, unreachable module split),
+//! so block splitting here only ever sees `Jump`-family and `Return*`
+//! targets -- there's no exception-handler lowering step for this `Cfg` to
+//! detect unreachable blocks after.
+
+use std::collections::BTreeSet;
+
+use super::{CodeObject, Constant, Instruction, Label, OpArgState};
+
+/// Whether `instr` never falls through to the next instruction.
+const fn is_terminator(instr: Instruction) -> bool {
+    matches!(
+        instr,
+        Instruction::Jump { .. } | Instruction::ReturnValue | Instruction::ReturnConst { .. }
+    )
+}
+
+/// One maximal straight-line run of instructions: no jump targets land
+/// inside it, and only its last instruction can branch or exit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// Offset of this block's first instruction.
+    pub start: Label,
+    /// Offset one past this block's last instruction.
+    pub end: Label,
+}
+
+/// A control-flow graph over a [`CodeObject`]'s decoded instructions.
+#[derive(Debug, Clone)]
+pub struct Cfg {
+    blocks: Vec<BasicBlock>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl Cfg {
+    /// Build the control-flow graph for `code`.
+    pub fn build<C: Constant>(code: &CodeObject<C>) -> Self {
+        let jump_targets = code.label_targets();
+
+        struct Decoded {
+            offset: u32,
+            size: u32,
+            instr: Instruction,
+        }
+        let mut decoded = Vec::new();
+        let mut state = OpArgState::default();
+        let mut seq_start = 0u32;
+        for (idx, &unit) in code.instructions.iter().enumerate() {
+            let idx = idx as u32;
+            let (instr, arg) = state.get(unit);
+            if instr == Instruction::ExtendedArg {
+                continue;
+            }
+            decoded.push(Decoded {
+                offset: seq_start,
+                size: arg.instr_size() as u32,
+                instr,
+            });
+            seq_start = idx + 1;
+        }
+
+        // Split points: offset 0, every jump target, and every offset
+        // right after a terminator.
+        let mut splits: BTreeSet<u32> = BTreeSet::new();
+        splits.insert(0);
+        for &Label(offset) in &jump_targets {
+            splits.insert(offset);
+        }
+        for d in &decoded {
+            if is_terminator(d.instr) || d.instr.label_arg().is_some() {
+                splits.insert(d.offset + d.size);
+            }
+        }
+
+        let mut boundaries: Vec<u32> = splits.into_iter().collect();
+        let code_len = code.instructions.len() as u32;
+        if boundaries.last() != Some(&code_len) {
+            boundaries.push(code_len);
+        }
+
+        let blocks: Vec<BasicBlock> = boundaries
+            .windows(2)
+            .map(|w| BasicBlock {
+                start: Label(w[0]),
+                end: Label(w[1]),
+            })
+            .collect();
+
+        let block_of = |offset: u32| -> Option<usize> {
+            blocks
+                .iter()
+                .position(|b| b.start.0 <= offset && offset < b.end.0)
+        };
+
+        let mut successors = vec![Vec::new(); blocks.len()];
+        let mut predecessors = vec![Vec::new(); blocks.len()];
+        for (i, block) in blocks.iter().enumerate() {
+            // The block's final instruction: the last decoded instruction
+            // that starts before `block.end`.
+            let Some(last_idx) = decoded.iter().rposition(|d| d.offset < block.end.0) else {
+                continue;
+            };
+            let last = &decoded[last_idx];
+
+            if !is_terminator(last.instr) {
+                if let Some(next) = block_of(block.end.0) {
+                    successors[i].push(next);
+                }
+            }
+            if let Some(Label(target)) = last.instr.label_arg() {
+                if let Some(tgt) = block_of(target) {
+                    successors[i].push(tgt);
+                }
+            }
+        }
+        for (i, succs) in successors.iter().enumerate() {
+            for &s in succs {
+                predecessors[s].push(i);
+            }
+        }
+
+        Self {
+            blocks,
+            successors,
+            predecessors,
+        }
+    }
+
+    pub fn blocks(&self) -> &[BasicBlock] {
+        &self.blocks
+    }
+
+    pub fn successors(&self, block: usize) -> &[usize] {
+        &self.successors[block]
+    }
+
+    pub fn predecessors(&self, block: usize) -> &[usize] {
+        &self.predecessors[block]
+    }
+
+    /// Every block reachable from `entry` by following successor edges.
+    pub fn reachable_from(&self, entry: usize) -> BTreeSet<usize> {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![entry];
+        while let Some(b) = stack.pop() {
+            if seen.insert(b) {
+                stack.extend(self.successors[b].iter().copied());
+            }
+        }
+        seen
+    }
+
+    /// Blocks in reverse-postorder from `entry`, suitable for forward
+    /// dataflow analyses.
+    pub fn reverse_postorder(&self, entry: usize) -> Vec<usize> {
+        let mut visited = vec![false; self.blocks.len()];
+        let mut postorder = Vec::new();
+
+        fn visit(cfg: &Cfg, node: usize, visited: &mut [bool], postorder: &mut Vec<usize>) {
+            if visited[node] {
+                return;
+            }
+            visited[node] = true;
+            for &succ in &cfg.successors[node] {
+                visit(cfg, succ, visited, postorder);
+            }
+            postorder.push(node);
+        }
+        visit(self, entry, &mut visited, &mut postorder);
+        postorder.reverse();
+        postorder
+    }
+
+    /// Compute the dominator tree rooted at `entry`, via the iterative
+    /// Cooper/Harvey/Kennedy algorithm (a fixpoint over a reverse-postorder
+    /// numbering, intersecting each block's predecessors' dominator chains).
+    pub fn dominators(&self, entry: usize) -> Dominators {
+        let rpo = self.reverse_postorder(entry);
+        let mut rpo_index = vec![None; self.blocks.len()];
+        for (i, &b) in rpo.iter().enumerate() {
+            rpo_index[b] = Some(i);
+        }
+
+        let mut idom: Vec<Option<usize>> = vec![None; self.blocks.len()];
+        idom[entry] = Some(entry);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &b in rpo.iter().skip(1) {
+                let mut new_idom = None;
+                for &p in &self.predecessors[b] {
+                    if idom[p].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => p,
+                        Some(cur) => intersect(&idom, &rpo_index, cur, p),
+                    });
+                }
+                if new_idom.is_some() && idom[b] != new_idom {
+                    idom[b] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        // The entry block is its own fixpoint seed, not dominated by anything.
+        idom[entry] = None;
+
+        Dominators { entry, idom }
+    }
+}
+
+/// The nearest common ancestor of `a` and `b` in the (partially built)
+/// dominator tree, walking each finger up to the other's reverse-postorder
+/// depth until they meet.
+fn intersect(idom: &[Option<usize>], rpo_index: &[Option<usize>], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        while rpo_index[a] > rpo_index[b] {
+            a = idom[a].expect("a is reachable from entry, so its idom chain terminates at entry");
+        }
+        while rpo_index[b] > rpo_index[a] {
+            b = idom[b].expect("b is reachable from entry, so its idom chain terminates at entry");
+        }
+    }
+    a
+}
+
+/// A [`Cfg`]'s dominator tree rooted at one entry block: every block's
+/// immediate dominator, the nearest block that every path from `entry` must
+/// pass through on its way there.
+#[derive(Debug, Clone)]
+pub struct Dominators {
+    entry: usize,
+    idom: Vec<Option<usize>>,
+}
+
+impl Dominators {
+    /// The entry block this tree was rooted at.
+    pub const fn entry(&self) -> usize {
+        self.entry
+    }
+
+    /// `block`'s immediate dominator, or `None` for the entry block itself
+    /// or a block unreachable from it.
+    pub fn immediate_dominator(&self, block: usize) -> Option<usize> {
+        self.idom[block]
+    }
+
+    /// Whether `a` dominates `b`: every path from `entry` to `b` passes
+    /// through `a`. Every block (reachable from `entry`) dominates itself.
+    pub fn dominates(&self, a: usize, b: usize) -> bool {
+        if a == b {
+            return true;
+        }
+        let mut cur = self.idom[b];
+        while let Some(d) = cur {
+            if d == a {
+                return true;
+            }
+            cur = self.idom[d];
+        }
+        false
+    }
+}
+
+/// Blocks unreachable from `entry` (typically block `0`) -- dead code a
+/// later pass can drop entirely.
+pub fn dead_blocks(cfg: &Cfg, entry: usize) -> Vec<usize> {
+    let reachable = cfg.reachable_from(entry);
+    (0..cfg.blocks().len())
+        .filter(|b| !reachable.contains(b))
+        .collect()
+}
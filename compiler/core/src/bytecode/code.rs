@@ -7,6 +7,7 @@ use super::{
     ConstantData,
     Oparg,
     OpargByte,
+    SourcePosition,
 };
 use crate::{OneIndexed, SourceLocation};
 use bitflags::bitflags;
@@ -163,6 +164,23 @@ impl<N: AsRef<str>> fmt::Debug for Arguments<'_, N> {
     }
 }
 
+/// Render one [`SourcePosition`] as CPython traceback-style `line:col-col` (or
+/// `line:col-line:col`, when the span crosses lines), 1-indexing the columns
+/// `positions()` stores 0-indexed. `None` when the table didn't record a
+/// column for this instruction (a synthetic opcode, or a
+/// [`PyCodeLocationInfoKind::None`]/`NoColumns` entry).
+fn format_span(pos: &SourcePosition) -> Option<String> {
+    let start_line = pos.start_line?;
+    let start_col = pos.start_col?;
+    let end_col = pos.end_col?;
+    let end_line = pos.end_line.unwrap_or(start_line);
+    Some(if end_line == start_line {
+        format!("{start_line}:{}-{}", start_col + 1, end_col + 1)
+    } else {
+        format!("{start_line}:{}-{end_line}:{}", start_col + 1, end_col + 1)
+    })
+}
+
 impl<C: Constant> CodeObject<C> {
     /// Get all arguments of the code object
     /// like inspect.getargs
@@ -218,6 +236,15 @@ impl<C: Constant> CodeObject<C> {
         let label_targets = self.label_targets();
         let line_digits = (3).max(self.locations.last().unwrap().row.to_string().len());
         let offset_digits = (4).max(self.instructions.len().to_string().len());
+        // Per-instruction `line:col-line:col` spans, CPython-traceback style
+        // (columns are stored 0-indexed, same as `co_positions`, so we add 1
+        // for display). `spans[i]` is `None` whenever `positions()` couldn't
+        // recover a column for that instruction (e.g. a synthetic opcode, or
+        // a `CodeObject` with an empty `linetable`). `span_width` is 0 in that case,
+        // so the column gutter below prints nothing rather than a blank
+        // column of spaces.
+        let spans: Vec<Option<String>> = self.positions().iter().map(format_span).collect();
+        let span_width = spans.iter().flatten().map(String::len).max().unwrap_or(0);
         let mut last_line = OneIndexed::MAX;
         let mut arg_state = OpArgState::default();
         for (offset, &instruction) in self.instructions.iter().enumerate() {
@@ -237,6 +264,12 @@ impl<C: Constant> CodeObject<C> {
             }
             write!(f, " ")?;
 
+            // optional column span, matching CPython's traceback `line:col` format
+            if span_width > 0 {
+                let span = spans[offset].as_deref().unwrap_or("");
+                write!(f, "{span:span_width$} ")?;
+            }
+
             // level indent
             for _ in 0..level {
                 write!(f, "    ")?;
@@ -257,6 +290,35 @@ impl<C: Constant> CodeObject<C> {
             instruction.fmt_dis(arg, f, self, expand_code_objects, 21, level)?;
             writeln!(f)?;
         }
+        self.display_exception_table(f, level)
+    }
+
+    /// Render `exceptiontable` as a `start to end -> handler [depth]` listing
+    /// (with a trailing `lasti` when that flag is set), the way CPython's
+    /// `dis.dis` prints `Exception handlers:`.
+    fn display_exception_table(&self, f: &mut fmt::Formatter<'_>, level: usize) -> fmt::Result {
+        let entries = match super::exceptions::decode_exception_table(&self.exceptiontable) {
+            Ok(entries) if !entries.is_empty() => entries,
+            _ => return Ok(()),
+        };
+        for _ in 0..level {
+            write!(f, "    ")?;
+        }
+        writeln!(f, "Exception handlers:")?;
+        for entry in entries {
+            for _ in 0..level {
+                write!(f, "    ")?;
+            }
+            writeln!(
+                f,
+                "  {} to {} -> {} [{}]{}",
+                entry.start,
+                entry.end,
+                entry.handler,
+                entry.depth,
+                if entry.lasti { " lasti" } else { "" }
+            )?;
+        }
         Ok(())
     }
 
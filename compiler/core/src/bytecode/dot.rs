@@ -0,0 +1,63 @@
+//! Graphviz DOT rendering of a [`CodeObject`]'s control-flow graph, for
+//! visually inspecting the compiler's output or the `cfg`/`verify`/
+//! `specialize` passes that walk it.
+//!
+//! [`to_dot`] partitions the instruction stream into [`Cfg`] basic blocks,
+//! renders each as a node listing its [`DisInstruction`]s (the same
+//! resolved-operand text `dis`-style tooling already uses), and draws edges
+//! for the fall-through and jump successors [`Cfg::build`] already computed.
+//!
+//! This crate has no `dis`-module binding or `pymodule!` machinery wired up
+//! anywhere in this snapshot (`vm`/`stdlib` have no such registration to
+//! extend), so the Python-visible helper this was also asked for isn't
+//! addressable here; `to_dot` is the `Instruction`-stream -> `String`
+//! facility itself, ready for whichever binding layer eventually wraps it.
+
+use std::fmt::Write as _;
+
+use super::{ArgVal, Cfg, CodeObject, Constant, DisInstruction, Label};
+
+/// Render `code`'s control-flow graph as a Graphviz DOT digraph.
+pub fn to_dot<C: Constant>(code: &CodeObject<C>) -> String {
+    let cfg = Cfg::build(code);
+    let by_offset: Vec<DisInstruction<'_, C>> = code.disassemble().collect();
+
+    let mut out = String::new();
+    writeln!(out, "digraph cfg {{").unwrap();
+    writeln!(out, "    node [shape=box, fontname=monospace];").unwrap();
+
+    for (i, block) in cfg.blocks().iter().enumerate() {
+        let mut label = format!("block {i} [{}, {})\\l", block.start.0, block.end.0);
+        for instr in by_offset
+            .iter()
+            .filter(|d| d.offset >= block.start.0 && d.offset < block.end.0)
+        {
+            let _ = write!(label, "{}: {} {}\\l", instr.offset, instr.opname, instr.argrepr);
+        }
+        writeln!(out, "    b{i} [label=\"{label}\"];").unwrap();
+    }
+
+    for (i, block) in cfg.blocks().iter().enumerate() {
+        let successors = cfg.successors(i);
+        let last = by_offset
+            .iter()
+            .rev()
+            .find(|d| d.offset < block.end.0 && d.offset >= block.start.0);
+        let jump_target = last.and_then(|d| match &d.argval {
+            ArgVal::Jump(Label(target)) => cfg
+                .blocks()
+                .iter()
+                .position(|b| b.start.0 == *target)
+                .filter(|t| successors.contains(t)),
+            _ => None,
+        });
+
+        for &succ in successors {
+            let kind = if Some(succ) == jump_target { "jump" } else { "fallthrough" };
+            writeln!(out, "    b{i} -> b{succ} [label=\"{kind}\"];").unwrap();
+        }
+    }
+
+    writeln!(out, "}}").unwrap();
+    out
+}
@@ -0,0 +1,72 @@
+//! Structured access to `exceptiontable`, so a debugger, the VM's unwinder,
+//! or [`CodeObject::verify`] can resolve which handler catches a raise at a
+//! given offset without reimplementing the table's binary layout.
+//!
+//! Each entry covers `[start, end)` and, like CPython's own exception table,
+//! carries the stack `depth` to restore on entry to the handler and a
+//! `lasti` flag marking whether the handler also wants the faulting
+//! instruction's offset pushed.
+
+use super::{CodeObject, Constant};
+
+/// One exception-table entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExceptionTableEntry {
+    /// Start of the covered instruction range (inclusive).
+    pub start: u32,
+    /// End of the covered instruction range (exclusive).
+    pub end: u32,
+    /// Instruction offset of the handler to jump to.
+    pub handler: u32,
+    /// Stack depth to restore before running the handler.
+    pub depth: u32,
+    /// Whether the handler also wants the faulting instruction's offset.
+    pub lasti: bool,
+}
+
+/// `exceptiontable` was not a whole number of entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MalformedExceptionTable;
+
+const ENTRY_SIZE: usize = 16;
+const LASTI_BIT: u32 = 1 << 31;
+
+/// Decode every entry in `exceptiontable`, in on-disk order.
+pub fn decode_exception_table(bytes: &[u8]) -> Result<Vec<ExceptionTableEntry>, MalformedExceptionTable> {
+    if bytes.len() % ENTRY_SIZE != 0 {
+        return Err(MalformedExceptionTable);
+    }
+    Ok(bytes
+        .chunks_exact(ENTRY_SIZE)
+        .map(|chunk| {
+            let packed = u32::from_le_bytes(chunk[12..16].try_into().unwrap());
+            ExceptionTableEntry {
+                start: u32::from_le_bytes(chunk[0..4].try_into().unwrap()),
+                end: u32::from_le_bytes(chunk[4..8].try_into().unwrap()),
+                handler: u32::from_le_bytes(chunk[8..12].try_into().unwrap()),
+                depth: packed & !LASTI_BIT,
+                lasti: packed & LASTI_BIT != 0,
+            }
+        })
+        .collect())
+}
+
+impl<C: Constant> CodeObject<C> {
+    /// The exception-table entry that would catch a raise at instruction
+    /// `offset`, if any. When multiple entries cover `offset` (a `try`
+    /// nested in another `try`), the narrowest one wins, same as CPython's
+    /// innermost-handler-first lookup.
+    pub fn exception_handler(&self, offset: u32) -> Option<ExceptionTableEntry> {
+        decode_exception_table(&self.exceptiontable)
+            .ok()?
+            .into_iter()
+            .filter(|entry| entry.start <= offset && offset < entry.end)
+            .min_by_key(|entry| entry.end - entry.start)
+    }
+
+    /// The source line instruction `offset` maps to, decoded from
+    /// `linetable` via [`CodeObject::positions`].
+    pub fn line_for(&self, offset: u32) -> Option<crate::OneIndexed> {
+        self.positions().get(offset as usize).copied()?.start_line
+    }
+}
@@ -0,0 +1,170 @@
+//! A [`ConstantBag`] whose name table entries are interned symbols rather
+//! than owned `String`s.
+//!
+//! `BasicBag::make_name` allocates a fresh `String` every time, but
+//! `NameIdx`-bearing opcodes (`LoadName`, `StoreGlobal`, `LoadAttr`,
+//! `ImportFrom`, ...) repeat the same handful of identifiers constantly --
+//! `self`, `__init__`, the same imported module name from every call site
+//! that imports it. [`Sym`] is a 32-bit index into a shared string table, so
+//! repeated names collapse to one table entry and comparing/copying a name
+//! is a word-sized operation instead of a `String` clone. [`resolve`] gets
+//! the text back for disassembly and `Display`.
+//!
+//! The table is a single per-thread interner (like rustc's own `Symbol`):
+//! that's what lets [`Sym`]s minted while compiling one code object stay
+//! valid, and still dedup, against names minted while compiling a nested one
+//! in the same compile. A [`Sym`] resolves only on the thread that minted it.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+use malachite_bigint::BigInt;
+use num_complex::Complex64;
+use rustpython_wtf8::Wtf8Buf;
+
+use super::{BorrowedConstant, CodeObject, Constant, ConstantBag};
+
+#[derive(Default)]
+struct Interner {
+    strings: Vec<&'static Wtf8Buf>,
+    ids: HashMap<&'static str, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&id) = self.ids.get(s) {
+            return id;
+        }
+        // Leaked once per distinct name; reclaimed only at process exit, same
+        // trade-off rustc's own interner makes for the same reason.
+        let leaked: &'static Wtf8Buf = Box::leak(Box::new(Wtf8Buf::from(s.to_owned())));
+        let id = self.strings.len() as u32;
+        self.strings.push(leaked);
+        self.ids.insert(leaked.as_str().unwrap_or_default(), id);
+        id
+    }
+
+    fn get(&self, id: u32) -> &'static Wtf8Buf {
+        self.strings[id as usize]
+    }
+}
+
+thread_local! {
+    static TABLE: RefCell<Interner> = RefCell::new(Interner::default());
+}
+
+/// An interned name: a word-sized, `Copy`, hashable handle into the shared
+/// string table, in place of an owned `String`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sym(u32);
+
+impl Sym {
+    /// Intern `name`, reusing the existing symbol if it was already seen.
+    pub fn intern(name: &str) -> Self {
+        Self(TABLE.with(|t| t.borrow_mut().intern(name)))
+    }
+}
+
+impl AsRef<str> for Sym {
+    fn as_ref(&self) -> &str {
+        let leaked = TABLE.with(|t| t.borrow().get(self.0));
+        leaked.as_str().unwrap_or_default()
+    }
+}
+
+impl fmt::Debug for Sym {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sym({:?})", self.as_ref())
+    }
+}
+
+/// Resolve `sym` back to the text it was interned from, for disassembly or
+/// `Display`.
+pub fn resolve(sym: Sym) -> &'static Wtf8Buf {
+    TABLE.with(|t| t.borrow().get(sym.0))
+}
+
+/// A constant whose embedded code objects' name tables are [`Sym`]s.
+///
+/// Structurally the same shape as [`ConstantData`](super::ConstantData); it
+/// has to be its own type (rather than reusing `ConstantData`) because a
+/// `Constant` impl's `Name` is fixed per concrete type, and `ConstantData`'s
+/// is already pinned to `String`.
+#[derive(Clone)]
+pub enum SymConstant {
+    Tuple(Vec<SymConstant>),
+    Integer(BigInt),
+    Float(f64),
+    Complex(Complex64),
+    Boolean(bool),
+    Str(Wtf8Buf),
+    Bytes(Vec<u8>),
+    Code(Box<CodeObject<SymConstant>>),
+    None,
+    Ellipsis,
+}
+
+impl Constant for SymConstant {
+    type Name = Sym;
+
+    fn borrow_constant(&self) -> BorrowedConstant<'_, Self> {
+        match self {
+            Self::Integer(value) => BorrowedConstant::Integer { value },
+            Self::Float(value) => BorrowedConstant::Float { value: *value },
+            Self::Complex(value) => BorrowedConstant::Complex { value: *value },
+            Self::Boolean(value) => BorrowedConstant::Boolean { value: *value },
+            Self::Str(value) => BorrowedConstant::Str { value },
+            Self::Bytes(value) => BorrowedConstant::Bytes { value },
+            Self::Code(code) => BorrowedConstant::Code { code },
+            Self::Tuple(elements) => BorrowedConstant::Tuple { elements },
+            Self::None => BorrowedConstant::None,
+            Self::Ellipsis => BorrowedConstant::Ellipsis,
+        }
+    }
+}
+
+/// A [`ConstantBag`] that interns every name it's asked to make via the
+/// shared [`Sym`] table.
+#[derive(Clone, Copy)]
+pub struct InternBag;
+
+impl ConstantBag for InternBag {
+    type Constant = SymConstant;
+
+    fn make_constant<C: Constant>(&self, constant: BorrowedConstant<'_, C>) -> Self::Constant {
+        match constant {
+            BorrowedConstant::Integer { value } => SymConstant::Integer(value.clone()),
+            BorrowedConstant::Float { value } => SymConstant::Float(value),
+            BorrowedConstant::Complex { value } => SymConstant::Complex(value),
+            BorrowedConstant::Boolean { value } => SymConstant::Boolean(value),
+            BorrowedConstant::Str { value } => SymConstant::Str(value.to_owned()),
+            BorrowedConstant::Bytes { value } => SymConstant::Bytes(value.to_owned()),
+            BorrowedConstant::Code { code } => SymConstant::Code(Box::new(code.map_clone_bag(self))),
+            BorrowedConstant::Tuple { elements } => SymConstant::Tuple(
+                elements
+                    .iter()
+                    .map(|c| self.make_constant(c.borrow_constant()))
+                    .collect(),
+            ),
+            BorrowedConstant::None => SymConstant::None,
+            BorrowedConstant::Ellipsis => SymConstant::Ellipsis,
+        }
+    }
+
+    fn make_int(&self, value: BigInt) -> Self::Constant {
+        SymConstant::Integer(value)
+    }
+
+    fn make_tuple(&self, elements: impl Iterator<Item = Self::Constant>) -> Self::Constant {
+        SymConstant::Tuple(elements.collect())
+    }
+
+    fn make_code(&self, code: CodeObject<Self::Constant>) -> Self::Constant {
+        SymConstant::Code(Box::new(code))
+    }
+
+    fn make_name(&self, name: &str) -> <Self::Constant as Constant>::Name {
+        Sym::intern(name)
+    }
+}
@@ -1,8 +1,31 @@
-use crate::byecode::Oparg;
+use super::{Instruction, Oparg, OpargByte};
+use crate::marshal::MarshalError;
 use bitflags::bitflags;
 
 pub trait OpargFamilyMember: Copy {
-    fn try_from_u8(raw: u8) -> Result<Self, crate::marshal::MarshalError>;
+    fn try_from_u8(raw: u8) -> Result<Self, MarshalError>;
+}
+
+/// A minimal byte sink, so [`Encodable`] doesn't have to commit to `Vec<u8>`
+/// specifically -- the marshal writer, a bytecode-transforming pass, or a
+/// fixed-size scratch buffer can all implement it.
+pub trait Buffer {
+    fn write(&mut self, b: u8);
+}
+
+impl Buffer for Vec<u8> {
+    fn write(&mut self, b: u8) {
+        self.push(b);
+    }
+}
+
+/// The reverse of [`OpargFamilyMember::try_from_u8`]: serialize a decoded
+/// oparg value back into raw bytes. `encode_len` lets a caller pre-size a
+/// buffer before a bulk encode instead of reallocating as it goes.
+pub trait Encodable: Copy {
+    fn encode(self, buf: &mut impl Buffer);
+
+    fn encode_len(self) -> usize;
 }
 
 /// Internal helper for [`oparg_enum!`].
@@ -11,16 +34,16 @@ pub trait OpargFamilyMember: Copy {
 /// - `TryFrom<u8>`
 /// - `TryFrom<OpargByte>`
 /// - `TryFrom<Oparg>`
-/// - `Into<Oparg>`
-/// - [`OpargType`](crate::bytecode::OpargType)
+/// - `From<$name> for Oparg`
+/// - `From<$name> for u8`
+/// - `From<$name> for u32`
 ///
 /// Should not be used directly outside of macro expansion.
 ///
 /// # Safety
 ///
 /// The generated conversion performs strict range checking and
-/// returns [`MarshalError::InvalidBytecode`](crate::marshal::MarshalError::InvalidBytecode)
-/// for any unmapped operand value.
+/// returns [`MarshalError::InvalidBytecode`] for any unmapped operand value.
 macro_rules! oparg_enum_impl {
     (enum $name:ident { $($(#[$var_attr:meta])* $var:ident = $value:literal,)* }) => {
         impl OpargFamilyMember for $name {
@@ -32,18 +55,18 @@ macro_rules! oparg_enum_impl {
             }
         }
 
-        /*
-        impl From<$name> for $crate::bytecode::Oparg {
-            fn from(oparg: $name) -> Self {
-                Self::from(oparg as u8)
+        impl TryFrom<u8> for $name {
+            type Error = $crate::marshal::MarshalError;
+
+            fn try_from(raw: u8) -> Result<Self, Self::Error> {
+                <Self as OpargFamilyMember>::try_from_u8(raw)
             }
         }
-*/
-        /*
+
         impl TryFrom<$crate::bytecode::OpargByte> for $name {
             type Error = $crate::marshal::MarshalError;
 
-            fn try_from(oparg: $crate::bytecode::OpargByte) -> Result<Self, Self::Err> {
+            fn try_from(oparg: $crate::bytecode::OpargByte) -> Result<Self, Self::Error> {
                 Self::try_from(u8::from(oparg))
             }
         }
@@ -51,27 +74,109 @@ macro_rules! oparg_enum_impl {
         impl TryFrom<$crate::bytecode::Oparg> for $name {
             type Error = $crate::marshal::MarshalError;
 
-            fn try_from(oparg: $crate::bytecode::Oparg) -> Result<Self, Self::Err> {
-                Self::try_from(u8::try_from(oparg).map_err(|_| Self::Error::InvalidBytecode)?)
+            fn try_from(oparg: $crate::bytecode::Oparg) -> Result<Self, Self::Error> {
+                Self::try_from(u8::try_from(*oparg).map_err(|_| Self::Error::InvalidBytecode)?)
+            }
+        }
+
+        impl From<$name> for $crate::bytecode::Oparg {
+            fn from(oparg: $name) -> Self {
+                Self::from(oparg as u8 as u32)
             }
         }
-        */
 
-/*
         impl From<$name> for u8 {
             fn from(oparg: $name) -> Self {
                 oparg as u8
             }
         }
-*/
-        /*
+
         impl From<$name> for u32 {
             fn from(oparg: $name) -> Self {
                 u8::from(oparg) as u32
             }
         }
-        */
 
+        impl $crate::bytecode::Encodable for $name {
+            fn encode(self, buf: &mut impl $crate::bytecode::Buffer) {
+                buf.write(self as u8);
+            }
+
+            fn encode_len(self) -> usize {
+                1
+            }
+        }
+
+        impl $name {
+            /// The variant's name, for a textual bytecode dump (e.g. `dis`
+            /// rendering `RESUME` opargs as `AtFuncStart` instead of `0`).
+            pub fn mnemonic(&self) -> &'static str {
+                match self {
+                    $(Self::$var => stringify!($var),)*
+                }
+            }
+
+            /// The inverse of [`Self::mnemonic`], so a textual assembler can
+            /// reconstruct the operand byte from a disassembly listing.
+            pub fn from_mnemonic(name: &str) -> Result<Self, $crate::marshal::MarshalError> {
+                Ok(match name {
+                    $(stringify!($var) => Self::$var,)*
+                    _ => return Err($crate::marshal::MarshalError::InvalidBytecode),
+                })
+            }
+        }
+    };
+}
+
+/// Implements a textual rendering/re-parse layer for a `bitflags!` struct,
+/// mirroring what [`oparg_enum_impl!`] generates for plain enums: `Display`
+/// joins every set flag's name with `|` (or `0` for an empty set), followed
+/// by any set bits no whole flag covers as one `0x..` hex term, and
+/// `from_mnemonic` is its inverse for a textual assembler.
+macro_rules! bitflags_mnemonic {
+    ($name:ident) => {
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let mut iter = self.iter_names();
+                let mut names: Vec<String> =
+                    iter.by_ref().map(|(name, _)| name.to_owned()).collect();
+                let rest = iter.remaining().bits();
+                if rest != 0 {
+                    names.push(format!("{rest:#x}"));
+                }
+                if names.is_empty() {
+                    write!(f, "0")
+                } else {
+                    write!(f, "{}", names.join("|"))
+                }
+            }
+        }
+
+        impl $name {
+            /// The inverse of [`Display`](std::fmt::Display): parse a
+            /// `|`-joined list of flag names back into the packed byte.
+            pub fn from_mnemonic(text: &str) -> Result<Self, $crate::marshal::MarshalError> {
+                if text == "0" {
+                    return Ok(Self::empty());
+                }
+                let mut result = Self::empty();
+                for part in text.split('|') {
+                    if let Some(hex) = part.strip_prefix("0x") {
+                        let bits = u8::from_str_radix(hex, 16)
+                            .map_err(|_| $crate::marshal::MarshalError::InvalidBytecode)?;
+                        result |= Self::from_bits(bits)
+                            .ok_or($crate::marshal::MarshalError::InvalidBytecode)?;
+                        continue;
+                    }
+                    let (_, flag) = Self::all()
+                        .iter_names()
+                        .find(|(name, _)| *name == part)
+                        .ok_or($crate::marshal::MarshalError::InvalidBytecode)?;
+                    result |= flag;
+                }
+                Ok(result)
+            }
+        }
     };
 }
 
@@ -115,6 +220,90 @@ macro_rules! oparg_enum {
     };
 }
 
+/// A value that can occupy a fixed-width sub-field of a packed oparg byte:
+/// either an [`OpargFamilyMember`] enum, or a single flag bit as `bool`.
+pub trait PackedField: Copy {
+    fn pack(self) -> u8;
+    fn unpack(bits: u8) -> Result<Self, MarshalError>;
+}
+
+impl PackedField for bool {
+    fn pack(self) -> u8 {
+        self as u8
+    }
+
+    fn unpack(bits: u8) -> Result<Self, MarshalError> {
+        match bits {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(MarshalError::InvalidBytecode),
+        }
+    }
+}
+
+impl<T: OpargFamilyMember + Into<u8>> PackedField for T {
+    fn pack(self) -> u8 {
+        self.into()
+    }
+
+    fn unpack(bits: u8) -> Result<Self, MarshalError> {
+        Self::try_from_u8(bits)
+    }
+}
+
+/// Declares a struct packing several fixed-width sub-fields into a single
+/// byte-sized oparg, the multi-field analogue of [`oparg_enum!`]'s
+/// single-field enums. Each field is `name: Type = start..end`, a
+/// half-open bit range; `Type` is anything implementing [`PackedField`].
+///
+/// Generates, for the struct: a typed getter per field, a `try_from_u8` that
+/// rejects a byte if any sub-field's bits don't decode (reserved bit
+/// patterns included), and a `to_u8` packer that asserts no field's encoded
+/// value overflows its declared width before OR-ing it into place.
+macro_rules! oparg_packed {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($field:ident : $ty:ty = $start:literal..$end:literal),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+        $vis struct $name {
+            $($field: $ty,)*
+        }
+
+        impl $name {
+            $(
+                pub fn $field(&self) -> $ty {
+                    self.$field
+                }
+            )*
+
+            pub fn try_from_u8(raw: u8) -> Result<Self, $crate::marshal::MarshalError> {
+                $(
+                    let mask: u8 = (1u16.checked_shl($end - $start).unwrap() - 1) as u8;
+                    let bits = (raw >> $start) & mask;
+                    let $field = <$ty as PackedField>::unpack(bits)?;
+                )*
+                Ok(Self { $($field,)* })
+            }
+
+            pub fn to_u8(self) -> u8 {
+                let mut out: u8 = 0;
+                $(
+                    let width = $end - $start;
+                    let mask: u8 = (1u16.checked_shl(width).unwrap() - 1) as u8;
+                    let bits = PackedField::pack(self.$field);
+                    assert!(bits & !mask == 0, "field `{}` overflows its {}-bit width", stringify!($field), width);
+                    out |= bits << $start;
+                )*
+                out
+            }
+        }
+    };
+}
+
 // https://github.com/python/cpython/blob/a15ae614deb58f78f9f4aa11ed18a0afc6a9df7d/Include/internal/pycore_opcode_utils.h#L61-L65
 oparg_enum!(
     /// Values used in the oparg for `RealOpcode::Resume`.
@@ -140,6 +329,18 @@ bitflags! {
     }
 }
 
+impl Encodable for MakeFunctionFlags {
+    fn encode(self, buf: &mut impl Buffer) {
+        buf.write(self.bits());
+    }
+
+    fn encode_len(self) -> usize {
+        1
+    }
+}
+
+bitflags_mnemonic!(MakeFunctionFlags);
+
 // https://github.com/python/cpython/blob/a15ae614deb58f78f9f4aa11ed18a0afc6a9df7d/Include/internal/pycore_opcode_utils.h#L67-L68
 bitflags! {
     #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -149,6 +350,67 @@ bitflags! {
     }
 }
 
+impl Encodable for ResumeOpargMask {
+    fn encode(self, buf: &mut impl Buffer) {
+        buf.write(self.bits());
+    }
+
+    fn encode_len(self) -> usize {
+        1
+    }
+}
+
+bitflags_mnemonic!(ResumeOpargMask);
+
+oparg_packed! {
+    /// The typed, multi-field equivalent of [`ResumeOpargMask`]: bits 0-1
+    /// are the [`ResumeOparg`] location, bit 2 is the `DEPTH1` flag.
+    pub struct ResumeOpargPacked {
+        location: ResumeOparg = 0..2,
+        depth1: bool = 2..3,
+    }
+}
+
+oparg_enum!(
+    /// The comparison performed by `CompareOp`, packed into the low 3 bits
+    /// of its oparg (see [`CompareOperandOparg`]).
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+    #[repr(u8)]
+    pub enum CompareOperatorOparg {
+        Lt = 0,
+        Le = 1,
+        Eq = 2,
+        Ne = 3,
+        Gt = 4,
+        Ge = 5,
+    }
+);
+
+impl CompareOperatorOparg {
+    /// This operator's Python spelling, e.g. `"<="`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+}
+
+oparg_packed! {
+    /// `CompareOp`'s oparg: bits 0-2 select the [`CompareOperatorOparg`],
+    /// bit 3 is set when the result should be coerced to `bool` (the fast
+    /// path for `if a < b:` rather than `(a < b)`, which skips materializing
+    /// the comparison's rich result).
+    pub struct CompareOperandOparg {
+        op: CompareOperatorOparg = 0..3,
+        coerce_bool: bool = 3..4,
+    }
+}
+
 // https://github.com/python/cpython/blob/a15ae614deb58f78f9f4aa11ed18a0afc6a9df7d/Include/internal/pycore_intrinsics.h#L8-L20
 oparg_enum!(
     /// Intrinsic function for `RealOpcde::CallIntrinsic1`.
@@ -174,6 +436,26 @@ oparg_enum!(
     }
 );
 
+impl IntrinsicFunction1Oparg {
+    /// This intrinsic's CPython `dis` name, e.g. `"INTRINSIC_IMPORT_STAR"`.
+    pub fn dis_name(&self) -> &'static str {
+        match self {
+            Self::Invalid => "INTRINSIC_INVALID",
+            Self::Print => "INTRINSIC_PRINT",
+            Self::ImportStar => "INTRINSIC_IMPORT_STAR",
+            Self::StopIterationError => "INTRINSIC_STOPITERATION_ERROR",
+            Self::AsyncGenWrap => "INTRINSIC_ASYNC_GEN_WRAP",
+            Self::UnaryPositive => "INTRINSIC_UNARY_POSITIVE",
+            Self::ListToTuple => "INTRINSIC_LIST_TO_TUPLE",
+            Self::TypeVar => "INTRINSIC_TYPEVAR",
+            Self::ParamSpec => "INTRINSIC_PARAMSPEC",
+            Self::TypeVarTuple => "INTRINSIC_TYPEVARTUPLE",
+            Self::SubscriptGeneric => "INTRINSIC_SUBSCRIPT_GENERIC",
+            Self::TypeAlias => "INTRINSIC_TYPEALIAS",
+        }
+    }
+}
+
 // https://github.com/python/cpython/blob/a15ae614deb58f78f9f4aa11ed18a0afc6a9df7d/Include/internal/pycore_intrinsics.h#L25-L31
 oparg_enum!(
     /// Intrinsic function for `RealOpcode::CallIntrinsic2`
@@ -190,6 +472,20 @@ oparg_enum!(
     }
 );
 
+impl IntrinsicFunction2Oparg {
+    /// This intrinsic's CPython `dis` name, e.g. `"INTRINSIC_PREP_RERAISE_STAR"`.
+    pub fn dis_name(&self) -> &'static str {
+        match self {
+            Self::Invalid => "INTRINSIC_INVALID",
+            Self::PrepReraiseStar => "INTRINSIC_PREP_RERAISE_STAR",
+            Self::TypeVarWithBound => "INTRINSIC_TYPEVAR_WITH_BOUND",
+            Self::TypeVarWithConstraint => "INTRINSIC_TYPEVAR_WITH_CONSTRAINT",
+            Self::SetFunctionTypeParams => "INTRINSIC_SET_FUNCTION_TYPE_PARAMS",
+            Self::SetTypeparamDefault => "INTRINSIC_SET_TYPEPARAM_DEFAULT",
+        }
+    }
+}
+
 // https://github.com/python/cpython/blob/a15ae614deb58f78f9f4aa11ed18a0afc6a9df7d/Include/opcode.h#L10-L35
 oparg_enum!(
     #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -224,6 +520,41 @@ oparg_enum!(
     }
 );
 
+impl BinaryOperatorOparg {
+    /// This operator's Python spelling, e.g. `"+"` or, for the in-place
+    /// variants, `"+="`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::And => "&",
+            Self::FloorDivide => "//",
+            Self::Lshift => "<<",
+            Self::MatrixMultiply => "@",
+            Self::Multiply => "*",
+            Self::Remainder => "%",
+            Self::Or => "|",
+            Self::Power => "**",
+            Self::Rshift => ">>",
+            Self::Subtract => "-",
+            Self::TrueDivide => "/",
+            Self::Xor => "^",
+            Self::InplaceAdd => "+=",
+            Self::InplaceAnd => "&=",
+            Self::InplaceFloorDivide => "//=",
+            Self::InplaceLshift => "<<=",
+            Self::InplaceMatrixMultiply => "@=",
+            Self::InplaceMultiply => "*=",
+            Self::InplaceRemainder => "%=",
+            Self::InplaceOr => "|=",
+            Self::InplacePower => "**=",
+            Self::InplaceRshift => ">>=",
+            Self::InplaceSubtract => "-=",
+            Self::InplaceTrueDivide => "/=",
+            Self::InplaceXor => "^=",
+        }
+    }
+}
+
 // https://github.com/python/cpython/blob/a15ae614deb58f78f9f4aa11ed18a0afc6a9df7d/Include/ceval.h#L127-L134
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 bitflags! {
@@ -241,20 +572,213 @@ bitflags! {
     }
 }
 
+impl Encodable for FormatValueConversion {
+    fn encode(self, buf: &mut impl Buffer) {
+        buf.write(self.bits());
+    }
+
+    fn encode_len(self) -> usize {
+        1
+    }
+}
+
+bitflags_mnemonic!(FormatValueConversion);
+
 // https://github.com/python/cpython/blob/a15ae614deb58f78f9f4aa11ed18a0afc6a9df7d/Include/ceval.h#L127-L134
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 bitflags! {
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
     pub struct FormatValueSpec: u8 {
         const MASK = 0x04;
         const HAVE_SPEC = 0x04;
     }
 }
 
+bitflags_mnemonic!(FormatValueSpec);
+
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum OpargFamily<T: Into<Oparg>> {
     Resume(ResumeOparg),
     BinaryOperator(BinaryOperatorOparg),
     IntrinsicFunction2(IntrinsicFunction2Oparg),
     IntrinsicFunction1(IntrinsicFunction1Oparg),
+    MakeFunction(MakeFunctionFlags),
+    FormatValue(FormatValueConversion, FormatValueSpec),
+    CompareOperand(CompareOperandOparg),
+    /// The oparg is an opaque index/count (e.g. a const/name/jump target)
+    /// with no further structure this dispatcher understands.
     None(T),
 }
+
+impl OpargFamily<Oparg> {
+    /// Interpret `oparg` according to what `opcode` is known to pack into
+    /// it, giving one safe entry point for decoding any operand instead of
+    /// scattering `try_from_u8` calls (and their matching opcode) across the
+    /// VM.
+    pub fn decode(opcode: Instruction, oparg: Oparg) -> Result<Self, MarshalError> {
+        let raw = u8::try_from(*oparg).map_err(|_| MarshalError::InvalidBytecode)?;
+        Ok(match opcode {
+            Instruction::Resume { .. } => Self::Resume(ResumeOparg::try_from_u8(raw)?),
+            Instruction::BinaryOp { .. } => Self::BinaryOperator(BinaryOperatorOparg::try_from_u8(raw)?),
+            Instruction::CallIntrinsic1 { .. } => {
+                Self::IntrinsicFunction1(IntrinsicFunction1Oparg::try_from_u8(raw)?)
+            }
+            Instruction::CallIntrinsic2 { .. } => {
+                Self::IntrinsicFunction2(IntrinsicFunction2Oparg::try_from_u8(raw)?)
+            }
+            Instruction::MakeFunction { .. } => {
+                Self::MakeFunction(MakeFunctionFlags::from_bits(raw).ok_or(MarshalError::InvalidBytecode)?)
+            }
+            Instruction::FormatValue { .. } => {
+                let conversion = FormatValueConversion::from_bits(raw & !FormatValueSpec::MASK.bits())
+                    .ok_or(MarshalError::InvalidBytecode)?;
+                let spec = FormatValueSpec::from_bits(raw & FormatValueSpec::MASK.bits())
+                    .ok_or(MarshalError::InvalidBytecode)?;
+                Self::FormatValue(conversion, spec)
+            }
+            Instruction::CompareOp { .. } => {
+                Self::CompareOperand(CompareOperandOparg::try_from_u8(raw)?)
+            }
+            _ => Self::None(oparg),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roundtrips<T>(raw: u8)
+    where
+        T: OpargFamilyMember + Encodable,
+    {
+        let decoded = T::try_from_u8(raw).unwrap();
+        let mut buf = Vec::new();
+        decoded.encode(&mut buf);
+        assert_eq!(buf, vec![raw]);
+    }
+
+    #[test]
+    fn roundtrips_resume_oparg() {
+        for raw in 0..=3u8 {
+            assert_roundtrips::<ResumeOparg>(raw);
+        }
+    }
+
+    #[test]
+    fn roundtrips_binary_operator_oparg() {
+        for raw in 0..=25u8 {
+            assert_roundtrips::<BinaryOperatorOparg>(raw);
+        }
+    }
+
+    #[test]
+    fn roundtrips_intrinsic_function1_oparg() {
+        for raw in 0..=11u8 {
+            assert_roundtrips::<IntrinsicFunction1Oparg>(raw);
+        }
+    }
+
+    #[test]
+    fn roundtrips_intrinsic_function2_oparg() {
+        for raw in 0..=5u8 {
+            assert_roundtrips::<IntrinsicFunction2Oparg>(raw);
+        }
+    }
+
+    #[test]
+    fn roundtrips_make_function_flags() {
+        for raw in 0..=0x0fu8 {
+            let decoded = MakeFunctionFlags::from_bits(raw).unwrap();
+            let mut buf = Vec::new();
+            decoded.encode(&mut buf);
+            assert_eq!(buf, vec![raw]);
+        }
+    }
+
+    #[test]
+    fn mnemonic_roundtrips_for_enums() {
+        for raw in 0..=3u8 {
+            let decoded = ResumeOparg::try_from_u8(raw).unwrap();
+            assert_eq!(ResumeOparg::from_mnemonic(decoded.mnemonic()).unwrap(), decoded);
+        }
+        for raw in 0..=25u8 {
+            let decoded = BinaryOperatorOparg::try_from_u8(raw).unwrap();
+            assert_eq!(
+                BinaryOperatorOparg::from_mnemonic(decoded.mnemonic()).unwrap(),
+                decoded
+            );
+        }
+    }
+
+    #[test]
+    fn mnemonic_roundtrips_for_bitflags() {
+        for raw in 0..=0x0fu8 {
+            let decoded = MakeFunctionFlags::from_bits(raw).unwrap();
+            assert_eq!(MakeFunctionFlags::from_mnemonic(&decoded.to_string()).unwrap(), decoded);
+        }
+        for raw in 0..=0x07u8 {
+            let decoded = ResumeOpargMask::from_bits(raw).unwrap();
+            assert_eq!(ResumeOpargMask::from_mnemonic(&decoded.to_string()).unwrap(), decoded);
+        }
+    }
+
+    #[test]
+    fn resume_oparg_packed_roundtrips() {
+        for raw in 0..=0x07u8 {
+            let decoded = ResumeOpargPacked::try_from_u8(raw).unwrap();
+            assert_eq!(decoded.to_u8(), raw);
+        }
+    }
+
+    #[test]
+    fn resume_oparg_packed_decodes_field_boundary() {
+        // Bits 0-1 have 4 valid `ResumeOparg` values (0..=3), so no 2-bit
+        // location pattern is rejected; exercise the boundary between the
+        // `location` and `depth1` fields instead.
+        assert_eq!(ResumeOpargPacked::try_from_u8(0b011).unwrap().location(), ResumeOparg::AfterAwait);
+        assert!(ResumeOpargPacked::try_from_u8(0b100).unwrap().depth1());
+    }
+
+    #[test]
+    fn roundtrips_compare_operator_oparg() {
+        for raw in 0..=5u8 {
+            assert_roundtrips::<CompareOperatorOparg>(raw);
+        }
+    }
+
+    #[test]
+    fn compare_operator_oparg_rejects_out_of_range() {
+        for raw in 6..=u8::MAX {
+            assert!(CompareOperatorOparg::try_from_u8(raw).is_err());
+        }
+    }
+
+    #[test]
+    fn mnemonic_roundtrips_for_compare_operator_oparg() {
+        for raw in 0..=5u8 {
+            let decoded = CompareOperatorOparg::try_from_u8(raw).unwrap();
+            assert_eq!(
+                CompareOperatorOparg::from_mnemonic(decoded.mnemonic()).unwrap(),
+                decoded
+            );
+        }
+    }
+
+    #[test]
+    fn compare_operand_oparg_packed_roundtrips() {
+        for op_raw in 0..=5u8 {
+            for coerce_bool in [false, true] {
+                let packed = op_raw | ((coerce_bool as u8) << 3);
+                let decoded = CompareOperandOparg::try_from_u8(packed).unwrap();
+                assert_eq!(decoded.to_u8(), packed);
+                assert_eq!(decoded.coerce_bool(), coerce_bool);
+            }
+        }
+    }
+
+    #[test]
+    fn compare_operator_symbols() {
+        assert_eq!(CompareOperatorOparg::Lt.symbol(), "<");
+        assert_eq!(CompareOperatorOparg::Ge.symbol(), ">=");
+    }
+}
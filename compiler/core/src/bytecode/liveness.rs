@@ -0,0 +1,158 @@
+//! Backward liveness analysis over a [`CodeObject`]'s locals, for
+//! dead-store elimination.
+//!
+//! Classic backward dataflow over the [`Cfg`]: a local is live at a point if
+//! some reachable `LoadFast` of it hasn't yet been preceded (walking
+//! forward) by a `StoreFast` to the same slot. We walk each block's
+//! instructions in reverse, growing the live set at a `LoadFast` use and
+//! shrinking it at a `StoreFast` def, merge block boundaries by unioning a
+//! block's live-out from its successors' live-in, and iterate blocks to a
+//! fixpoint so the result is stable across loop back-edges.
+//!
+//! This table has only a plain `StoreFast`, not CPython's
+//! `StoreFastMaybeNull` variant (no such mnemonic exists in
+//! `instructions.in`), so [`analyze`]'s second output is the list of
+//! `StoreFast` offsets a later pass could drop entirely -- the "demote to
+//! `StoreFastMaybeNull`" half of the request has no variant to demote to
+//! here.
+
+use std::collections::BTreeSet;
+
+use super::{Cfg, CodeObject, Constant, Instruction, OpArgState};
+
+struct Decoded {
+    offset: u32,
+    instr: Instruction,
+    arg: u32,
+}
+
+fn decode<C: Constant>(code: &CodeObject<C>) -> Vec<Decoded> {
+    let mut out = Vec::new();
+    let mut state = OpArgState::default();
+    let mut seq_start = 0u32;
+    for (idx, &unit) in code.instructions.iter().enumerate() {
+        let idx = idx as u32;
+        let (instr, arg) = state.get(unit);
+        if instr == Instruction::ExtendedArg {
+            continue;
+        }
+        out.push(Decoded {
+            offset: seq_start,
+            instr,
+            arg: arg.as_u32(),
+        });
+        seq_start = idx + 1;
+    }
+    out
+}
+
+/// Whether `instr` uses or defines the local its oparg names.
+enum LocalOp {
+    Use,
+    Def,
+}
+
+const fn local_op(instr: Instruction) -> Option<LocalOp> {
+    match instr {
+        Instruction::LoadFast => Some(LocalOp::Use),
+        Instruction::StoreFast => Some(LocalOp::Def),
+        _ => None,
+    }
+}
+
+/// The set of locals (by varname index) live immediately before each
+/// instruction offset.
+#[derive(Debug, Clone, Default)]
+pub struct Liveness {
+    live_before: std::collections::BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl Liveness {
+    /// Locals live immediately before the instruction at `offset`. Empty if
+    /// `offset` isn't a real instruction boundary, or nothing is live there.
+    pub fn live_before(&self, offset: u32) -> BTreeSet<u32> {
+        self.live_before.get(&offset).cloned().unwrap_or_default()
+    }
+}
+
+/// Compute per-offset liveness for `code`'s locals, plus the offsets of
+/// every `StoreFast` whose value is never read before either the next store
+/// to the same slot or the end of every path out of it -- a dead store a
+/// later pass can remove outright.
+pub fn analyze<C: Constant>(code: &CodeObject<C>) -> (Liveness, Vec<u32>) {
+    let decoded = decode(code);
+    let cfg = Cfg::build(code);
+
+    let block_members: Vec<Vec<usize>> = cfg
+        .blocks()
+        .iter()
+        .map(|b| {
+            decoded
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| d.offset >= b.start.0 && d.offset < b.end.0)
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect();
+
+    let mut live_in: Vec<BTreeSet<u32>> = vec![BTreeSet::new(); cfg.blocks().len()];
+    loop {
+        let mut changed = false;
+        for b in 0..cfg.blocks().len() {
+            let mut live: BTreeSet<u32> = cfg
+                .successors(b)
+                .iter()
+                .flat_map(|&s| live_in[s].iter().copied())
+                .collect();
+            for &idx in block_members[b].iter().rev() {
+                let d = &decoded[idx];
+                match local_op(d.instr) {
+                    Some(LocalOp::Use) => {
+                        live.insert(d.arg);
+                    }
+                    Some(LocalOp::Def) => {
+                        live.remove(&d.arg);
+                    }
+                    None => {}
+                }
+            }
+            if live != live_in[b] {
+                live_in[b] = live;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut live_before = std::collections::BTreeMap::new();
+    let mut dead_stores = Vec::new();
+    for b in 0..cfg.blocks().len() {
+        let mut live: BTreeSet<u32> = cfg
+            .successors(b)
+            .iter()
+            .flat_map(|&s| live_in[s].iter().copied())
+            .collect();
+        for &idx in block_members[b].iter().rev() {
+            let d = &decoded[idx];
+            match local_op(d.instr) {
+                Some(LocalOp::Use) => {
+                    live.insert(d.arg);
+                }
+                Some(LocalOp::Def) => {
+                    if !live.contains(&d.arg) {
+                        dead_stores.push(d.offset);
+                    }
+                    live.remove(&d.arg);
+                }
+                None => {}
+            }
+            live_before.insert(d.offset, live.clone());
+        }
+    }
+    dead_stores.sort_unstable();
+
+    (Liveness { live_before }, dead_stores)
+}
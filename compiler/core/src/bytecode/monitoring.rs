@@ -0,0 +1,140 @@
+//! `sys.monitoring` (PEP 669) tool/event bookkeeping.
+//!
+//! CPython's PEP 669 monitoring rewrites a code object's instruction array
+//! in place, swapping each event-producing instruction (`CALL`,
+//! `JUMP_BACKWARD`, `RETURN_VALUE`, ...) for an `INSTRUMENTED_*` sibling that
+//! fires the registered callback before falling through to the original
+//! instruction's behavior. This tree's [`Instruction`](super::Instruction)
+//! table has no `Instrumented*` family to swap in -- every mnemonic here
+//! *is* the base form, so there's no instrumented opcode for
+//! [`instrumented_form`] to produce, and correspondingly nothing for
+//! [`base_instruction`] to map back from (it's total, but always `None`).
+//!
+//! What's genuinely self-contained and implementable without that family:
+//! the tool-ID/event-mask registry PEP 669 uses to decide *which* sites a
+//! future instrumentation pass would rewrite, and the per-offset local
+//! disable a callback can request.
+
+use bitflags::bitflags;
+
+/// PEP 669 reserves 8 tool IDs (`sys.monitoring.DEBUGGER_ID` and friends);
+/// `sys.monitoring.register_callback` rejects anything outside `0..8`.
+pub const MAX_TOOLS: u8 = 8;
+
+/// One of the 8 concurrent monitoring tool slots.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ToolId(u8);
+
+impl ToolId {
+    /// Construct a tool ID, or `None` if `id >= `[`MAX_TOOLS`].
+    pub const fn new(id: u8) -> Option<Self> {
+        if id < MAX_TOOLS { Some(Self(id)) } else { None }
+    }
+
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+bitflags! {
+    /// The subset of `sys.monitoring` event kinds this request names.
+    #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+    pub struct EventSet: u32 {
+        const CALL = 1 << 0;
+        const LINE = 1 << 1;
+        const JUMP = 1 << 2;
+        const BRANCH = 1 << 3;
+        const PY_RETURN = 1 << 4;
+        const PY_YIELD = 1 << 5;
+    }
+}
+
+/// A monitoring tool's per-event-kind interest, as `sys.monitoring` would
+/// track it after `set_events`/`set_local_events`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ToolRegistry {
+    masks: [EventSet; MAX_TOOLS as usize],
+}
+
+impl ToolRegistry {
+    pub const fn new() -> Self {
+        Self {
+            masks: [EventSet::empty(); MAX_TOOLS as usize],
+        }
+    }
+
+    /// `sys.monitoring.set_events(tool_id, event_set)`: replace `tool`'s
+    /// global event mask.
+    pub fn set_events(&mut self, tool: ToolId, events: EventSet) {
+        self.masks[tool.get() as usize] = events;
+    }
+
+    pub fn events(&self, tool: ToolId) -> EventSet {
+        self.masks[tool.get() as usize]
+    }
+
+    /// The union of every registered tool's interest: the events a code
+    /// object would need instrumented for *some* tool to observe.
+    pub fn active_events(&self) -> EventSet {
+        self.masks
+            .iter()
+            .fold(EventSet::empty(), |acc, &m| acc | m)
+    }
+}
+
+/// A monitoring callback's `DISABLE` return value: turn an event kind off
+/// at one instruction offset without touching every other site watching
+/// the same event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LocalDisable {
+    pub offset: u32,
+    pub event: EventSet,
+}
+
+/// The instrumented sibling of `instr`, or `None` if either `instr` doesn't
+/// produce a monitoring event or (as in this table, always) no
+/// `Instrumented*` variant exists for it to become.
+#[inline]
+pub const fn instrumented_form(_instr: super::Instruction) -> Option<super::Instruction> {
+    None
+}
+
+/// The base instruction an `Instrumented*` variant was substituted for, or
+/// `None` if `instr` isn't an instrumented variant. This table has no
+/// `Instrumented*` family, so this is always `None`.
+#[inline]
+pub const fn base_instruction(_instr: super::Instruction) -> Option<super::Instruction> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytecode::Instruction;
+
+    #[test]
+    fn tool_id_rejects_out_of_range() {
+        assert!(ToolId::new(0).is_some());
+        assert!(ToolId::new(7).is_some());
+        assert!(ToolId::new(8).is_none());
+    }
+
+    #[test]
+    fn registry_unions_active_events_across_tools() {
+        let mut reg = ToolRegistry::new();
+        let debugger = ToolId::new(0).unwrap();
+        let profiler = ToolId::new(1).unwrap();
+        reg.set_events(debugger, EventSet::LINE | EventSet::CALL);
+        reg.set_events(profiler, EventSet::PY_RETURN);
+        assert_eq!(
+            reg.active_events(),
+            EventSet::LINE | EventSet::CALL | EventSet::PY_RETURN
+        );
+    }
+
+    #[test]
+    fn no_instrumented_family_exists_in_this_table() {
+        assert_eq!(instrumented_form(Instruction::CallFunction), None);
+        assert_eq!(base_instruction(Instruction::CallFunction), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Per-opcode dispatch via a small trait and a generated lookup table,
+//! instead of one growing match over [`Instruction`].
+//!
+//! [`Operation`] is implemented once per mnemonic by a zero-sized marker type
+//! (`operations!` below generates both the types and [`operation_for`]'s
+//! match arm from a single list, the same "one edit in one place" shape
+//! `build.rs` already gives `instructions.in`); every method but
+//! [`Operation::instruction`] has a default that delegates straight back to
+//! the generated [`Instruction`] accessors, so nothing here duplicates
+//! `label_arg`/`stack_effect`/`num_popped`/`num_pushed`/`is_adaptive` -- they
+//! stay defined exactly once, in `generated.rs`. Adding a mnemonic to
+//! `instructions.in` means adding its name to the `operations!` list below
+//! (and, if its behavior genuinely diverges from the defaults, overriding
+//! just that method on its marker type); no other arm changes. A call site
+//! can hold a `&'static dyn Operation` rather than an `Instruction` value,
+//! which is what would let [`Specializer`](super::Specializer) swap in a
+//! different handler per site without widening a shared match.
+//!
+//! This can't "keep `From<Instruction> for Opcode` working", as the request
+//! driving this module asked: no such impl exists to keep working. The
+//! `Opcode` type lives in a different crate module (`crate::opcode`, *not*
+//! this `bytecode` tree) and wraps `RealOpcode`/`PseudoOpcode` from
+//! `crate::opcodes`, a table that isn't vendored in this snapshot -- `Opcode`
+//! and this module's `Instruction` have never been bridged, so there's
+//! nothing to preserve.
+
+use super::{Instruction, Label};
+
+/// One opcode's decode-time metadata, dispatched through a vtable entry
+/// instead of a `match self` arm.
+pub trait Operation {
+    /// The instruction this handler answers for.
+    fn instruction(&self) -> Instruction;
+
+    #[inline]
+    fn label_arg(&self) -> Option<Label> {
+        self.instruction().label_arg()
+    }
+
+    #[inline]
+    fn stack_effect(&self, oparg: u32, jump: bool) -> i32 {
+        self.instruction().stack_effect(oparg, jump)
+    }
+
+    #[inline]
+    fn num_popped(&self, oparg: u32, jump: bool) -> i32 {
+        self.instruction().num_popped(oparg, jump)
+    }
+
+    #[inline]
+    fn num_pushed(&self, oparg: u32, jump: bool) -> i32 {
+        self.instruction().num_pushed(oparg, jump)
+    }
+
+    #[inline]
+    fn is_adaptive(&self) -> bool {
+        self.instruction().is_adaptive()
+    }
+}
+
+macro_rules! operations {
+    ($($name:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("[`Operation`] handler for [`Instruction::", stringify!($name), "`].")]
+            #[derive(Debug, Clone, Copy, Default)]
+            pub struct $name;
+
+            impl Operation for $name {
+                #[inline]
+                fn instruction(&self) -> Instruction {
+                    Instruction::$name
+                }
+            }
+        )+
+
+        /// `Instruction` -> its [`Operation`] handler, one arm per mnemonic
+        /// in `instructions.in`. The only match left in this module; every
+        /// other property a caller might want is reached through the
+        /// returned vtable instead.
+        pub fn operation_for(instr: Instruction) -> &'static dyn Operation {
+            match instr {
+                $(Instruction::$name => &$name,)+
+            }
+        }
+    };
+}
+
+operations![
+    Nop,
+    PopTop,
+    LoadConst,
+    ReturnConst,
+    LoadFast,
+    LoadGlobal,
+    StoreFast,
+    Jump,
+    JumpIfFalse,
+    JumpIfTrue,
+    PopJumpIfFalse,
+    ForIter,
+    Send,
+    ReturnValue,
+    BinaryOp,
+    CompareOp,
+    ContainsOp,
+    IsOp,
+    UnaryOp,
+    ToBool,
+    BuildList,
+    BuildTuple,
+    CallFunction,
+    Resume,
+    CallIntrinsic1,
+    CallIntrinsic2,
+    MakeFunction,
+    FormatValue,
+    ExtendedArg,
+];
@@ -0,0 +1,331 @@
+//! Peephole constant-folding and dead-code elimination over a [`CodeObject`].
+//!
+//! [`Cfg::build`] partitions the instruction stream into basic blocks once;
+//! everything below rewrites strictly inside one block at a time (a block's
+//! interior is never a jump target, so nothing outside it can observe a
+//! partial rewrite) and drops whole blocks [`Cfg::reachable_from`] can't
+//! reach from the entry at all. Three patterns are folded per block:
+//!
+//! - `LoadConst ; LoadConst ; BinaryOp` on two constants [`eval_binop`] knows
+//!   how to fold collapses to a single `LoadConst` of the precomputed result.
+//! - `LoadConst ; ToBool ; PopJumpIfFalse` with a constant of statically
+//!   known truthiness collapses to an unconditional `Jump` (branch always
+//!   taken) or disappears entirely (branch never taken). This table has no
+//!   `PopJumpIfTrue`/`ToBoolBool`/`ToBoolInt`/`ToBoolStr` family -- only the
+//!   generic `ToBool` and `PopJumpIfFalse` -- so that's the one shape this
+//!   handles.
+//! - `LoadConst ; PopTop` (a pushed value immediately discarded) disappears
+//!   entirely.
+//!
+//! Because folding can delete or merge instructions, every surviving or
+//! freshly synthesized instruction is tagged with the original offset(s) it
+//! stands in for. A final pass -- the same offset/size fixpoint `assembler`
+//! already runs for its own symbolic labels -- resolves every jump against
+//! that map and re-encodes the stream, so a jump that targeted now-deleted
+//! code lands on whatever instruction now occupies its place.
+//!
+//! Once this crate has a `Cargo.toml`, this pass should sit behind a
+//! `constant-optimization` feature (`#[cfg(feature = "constant-optimization")]`
+//! on [`optimize`]) so callers that want the unoptimized stream -- e.g. to
+//! keep line-for-line correspondence with source for a debugger -- can opt
+//! out; there's no feature table in this snapshot to wire that into yet.
+
+use std::collections::BTreeMap;
+
+use malachite_bigint::BigInt;
+use num_complex::Complex64;
+
+use super::{
+    Cfg, CodeObject, CodeUnit, Constant, ConstantBag, ConstantData, ConstantPoolBuilder,
+    Instruction, Oparg, OpArgState,
+};
+
+/// Run the optimizer, returning an equivalent code object. `bag` is used to
+/// intern any freshly folded constants.
+pub fn optimize<B: ConstantBag<Constant = ConstantData>>(
+    code: CodeObject<ConstantData>,
+    bag: B,
+) -> CodeObject<ConstantData> {
+    let decoded = decode(&code);
+    let cfg = Cfg::build(&code);
+    let reachable = cfg.reachable_from(0);
+
+    let mut pool = ConstantPoolBuilder::new(bag);
+    // Seed the pool with the existing constants in order, so any `LoadConst`
+    // left untouched keeps referencing the exact same slot.
+    for c in &*code.constants {
+        pool.intern(c.borrow_constant());
+    }
+
+    let mut master: Vec<Rewritten> = Vec::new();
+    let mut direct: BTreeMap<u32, usize> = BTreeMap::new();
+    for (block_idx, block) in cfg.blocks().iter().enumerate() {
+        if !reachable.contains(&block_idx) {
+            continue;
+        }
+        let slice: Vec<Decoded> = decoded
+            .iter()
+            .filter(|d| d.offset >= block.start.0 && d.offset < block.end.0)
+            .copied()
+            .collect();
+        peephole_block(&slice, &code.constants, &mut pool, &mut master, &mut direct);
+    }
+
+    let retarget = resolve_retargets(&decoded, &direct, master.len());
+    let instructions = assemble(&master, &retarget);
+
+    CodeObject {
+        instructions: instructions.into_boxed_slice(),
+        constants: pool.into_pool().into_boxed_slice(),
+        ..code
+    }
+}
+
+/// One decoded instruction paired with its byte offset, so label math survives
+/// the transform.
+#[derive(Clone, Copy)]
+struct Decoded {
+    offset: u32,
+    instr: Instruction,
+    arg: Oparg,
+}
+
+fn decode(code: &CodeObject<ConstantData>) -> Vec<Decoded> {
+    let mut out = Vec::new();
+    let mut state = OpArgState::default();
+    let mut seq_start = 0u32;
+    for (idx, &unit) in code.instructions.iter().enumerate() {
+        let idx = idx as u32;
+        let (instr, arg) = state.get(unit);
+        if instr == Instruction::ExtendedArg {
+            continue;
+        }
+        out.push(Decoded {
+            offset: seq_start,
+            instr,
+            arg,
+        });
+        seq_start = idx + 1;
+    }
+    out
+}
+
+/// One instruction of the rewritten stream, tagged with the original
+/// offset(s) it stands in for.
+struct Rewritten {
+    covers: Vec<u32>,
+    instr: Instruction,
+    arg: RewrittenArg,
+}
+
+enum RewrittenArg {
+    /// An oparg whose numeric value is already final.
+    Literal(u32),
+    /// A jump whose target is the original offset named here; resolved to an
+    /// index into the rewritten stream by [`resolve_retargets`].
+    Target(u32),
+}
+
+fn arg_of(d: &Decoded) -> RewrittenArg {
+    if d.instr.label_arg().is_some() {
+        RewrittenArg::Target(d.arg.as_u32())
+    } else {
+        RewrittenArg::Literal(d.arg.as_u32())
+    }
+}
+
+/// Peephole-rewrite one basic block's instructions, appending the result to
+/// `master` and recording, in `direct`, which `master` index now represents
+/// each surviving original offset.
+fn peephole_block<B: ConstantBag<Constant = ConstantData>>(
+    block: &[Decoded],
+    constants: &[ConstantData],
+    pool: &mut ConstantPoolBuilder<B>,
+    master: &mut Vec<Rewritten>,
+    direct: &mut BTreeMap<u32, usize>,
+) {
+    let mut i = 0;
+    while i < block.len() {
+        // LoadConst ; LoadConst ; BinaryOp, folding pure arithmetic.
+        if let [a, b, op, ..] = &block[i..] {
+            if matches!(
+                (a.instr, b.instr, op.instr),
+                (Instruction::LoadConst, Instruction::LoadConst, Instruction::BinaryOp)
+            ) {
+                let lhs = &constants[a.arg.as_u32() as usize];
+                let rhs = &constants[b.arg.as_u32() as usize];
+                if let Some(folded) = eval_binop(op.arg.as_u32(), lhs, rhs) {
+                    let idx = pool.intern(folded.borrow_constant());
+                    push(
+                        master,
+                        direct,
+                        vec![a.offset, b.offset, op.offset],
+                        Instruction::LoadConst,
+                        RewrittenArg::Literal(idx),
+                    );
+                    i += 3;
+                    continue;
+                }
+            }
+        }
+
+        // LoadConst ; ToBool ; PopJumpIfFalse, folding a statically known
+        // branch condition.
+        if let [load, to_bool, branch, ..] = &block[i..] {
+            if matches!(
+                (load.instr, to_bool.instr, branch.instr),
+                (Instruction::LoadConst, Instruction::ToBool, Instruction::PopJumpIfFalse)
+            ) {
+                let value = &constants[load.arg.as_u32() as usize];
+                if let Some(truthy) = is_truthy(value) {
+                    if !truthy {
+                        // Branch always taken: replace with an unconditional
+                        // jump to the same target.
+                        push(
+                            master,
+                            direct,
+                            vec![load.offset, to_bool.offset, branch.offset],
+                            Instruction::Jump,
+                            RewrittenArg::Target(branch.arg.as_u32()),
+                        );
+                    }
+                    // Branch never taken: the whole sequence is dropped and
+                    // falls through to whatever follows it.
+                    i += 3;
+                    continue;
+                }
+            }
+        }
+
+        // LoadConst ; PopTop, a pushed value immediately discarded.
+        if let [load, pop, ..] = &block[i..] {
+            if matches!((load.instr, pop.instr), (Instruction::LoadConst, Instruction::PopTop)) {
+                i += 2;
+                continue;
+            }
+        }
+
+        let d = block[i];
+        push(master, direct, vec![d.offset], d.instr, arg_of(d));
+        i += 1;
+    }
+}
+
+fn push(
+    master: &mut Vec<Rewritten>,
+    direct: &mut BTreeMap<u32, usize>,
+    covers: Vec<u32>,
+    instr: Instruction,
+    arg: RewrittenArg,
+) {
+    let idx = master.len();
+    for &off in &covers {
+        direct.insert(off, idx);
+    }
+    master.push(Rewritten { covers, instr, arg });
+}
+
+/// Map every original instruction offset to the `master` index that now
+/// represents it: itself if it's covered directly, otherwise the next
+/// surviving instruction that follows it (a jump into deleted code lands
+/// right after where that code used to be).
+fn resolve_retargets(
+    decoded: &[Decoded],
+    direct: &BTreeMap<u32, usize>,
+    master_len: usize,
+) -> BTreeMap<u32, usize> {
+    let mut retarget = BTreeMap::new();
+    let mut next = master_len;
+    for d in decoded.iter().rev() {
+        if let Some(&idx) = direct.get(&d.offset) {
+            next = idx;
+        }
+        retarget.insert(d.offset, next);
+    }
+    retarget
+}
+
+/// Evaluate a foldable binary op on two constants, if the combination is one we
+/// know how to fold. Returns `None` to bail conservatively.
+fn eval_binop(op: u32, lhs: &ConstantData, rhs: &ConstantData) -> Option<ConstantData> {
+    use ConstantData::Integer;
+    match (lhs, rhs) {
+        (Integer { value: a }, Integer { value: b }) => Some(Integer {
+            value: match op {
+                0 => a + b,
+                10 => a - b,
+                5 => a * b,
+                _ => return None,
+            },
+        }),
+        _ => None,
+    }
+}
+
+/// The Python truthiness of a constant, or `None` if it can't be decided
+/// without running arbitrary `__bool__`/`__len__` (only `Code` falls here).
+fn is_truthy(value: &ConstantData) -> Option<bool> {
+    match value {
+        ConstantData::Integer { value } => Some(*value != BigInt::from(0)),
+        ConstantData::Float { value } => Some(*value != 0.0),
+        ConstantData::Complex { value } => Some(*value != Complex64::new(0.0, 0.0)),
+        ConstantData::Boolean { value } => Some(*value),
+        ConstantData::Str { value } => Some(!value.is_empty()),
+        ConstantData::Bytes { value } => Some(!value.is_empty()),
+        ConstantData::Tuple { elements } => Some(!elements.is_empty()),
+        ConstantData::None => Some(false),
+        ConstantData::Ellipsis => Some(true),
+        ConstantData::Code { .. } => None,
+    }
+}
+
+/// Resolve every jump's target index to a final offset and re-encode the
+/// stream, iterating to a fixpoint since a target's encoded offset and an
+/// instruction's encoded size are mutually dependent -- the same scheme
+/// [`assembler::resolve_offsets`](super::assembler) uses for symbolic labels.
+fn assemble(master: &[Rewritten], retarget: &BTreeMap<u32, usize>) -> Vec<CodeUnit> {
+    let targets: Vec<Option<usize>> = master
+        .iter()
+        .map(|r| match r.arg {
+            RewrittenArg::Target(off) => Some(*retarget.get(&off).unwrap_or(&master.len())),
+            RewrittenArg::Literal(_) => None,
+        })
+        .collect();
+
+    let mut offsets = vec![0u32; master.len() + 1];
+    loop {
+        let mut changed = false;
+        let mut offset = 0u32;
+        for (i, r) in master.iter().enumerate() {
+            if offsets[i] != offset {
+                offsets[i] = offset;
+                changed = true;
+            }
+            let raw = match (&r.arg, targets[i]) {
+                (RewrittenArg::Target(_), Some(idx)) => offsets.get(idx).copied().unwrap_or(offset),
+                (RewrittenArg::Literal(value), _) => *value,
+                _ => 0,
+            };
+            offset += Oparg::new(raw).instr_size() as u32;
+        }
+        offsets[master.len()] = offset;
+        if !changed {
+            break;
+        }
+    }
+
+    let mut out = Vec::with_capacity(master.len() * 2);
+    for (i, r) in master.iter().enumerate() {
+        let raw = match (&r.arg, targets[i]) {
+            (RewrittenArg::Target(_), Some(idx)) => offsets[idx],
+            (RewrittenArg::Literal(value), _) => *value,
+            _ => 0,
+        };
+        let (ext, lo) = Oparg::new(raw).split();
+        for byte in ext {
+            out.push(CodeUnit::new(Instruction::ExtendedArg, byte));
+        }
+        out.push(CodeUnit::new(r.instr, lo));
+    }
+    out
+}
@@ -0,0 +1,77 @@
+//! Content-addressed constant pool construction.
+//!
+//! [`ConstantData`] already has exact-value [`PartialEq`]/[`Hash`] (floats and
+//! complex numbers compared by `to_bits`, so `NaN` still dedups against an
+//! identical `NaN`), which is everything [`ConstantPoolBuilder`] needs to sit
+//! on top of a [`ConstantBag`] and turn `make_constant` calls into stable
+//! `u32` indices a `LoadConst { idx }` can reference. This is the same
+//! `co_consts` dedup CPython's compiler does: two `LoadConst 1` loads in
+//! different functions that happen to both push the literal `1` share one
+//! pool slot, and the singletons (`None`, `Ellipsis`, the bools, small ints)
+//! collapse to exactly one slot each no matter how many times they're
+//! interned.
+
+use std::collections::HashMap;
+
+use super::{BorrowedConstant, Constant, ConstantBag, ConstantData};
+
+/// Builds a deduplicated [`ConstantData`] pool, handing back a stable `u32`
+/// index for every interned constant.
+///
+/// Every `intern` materializes its argument through `bag` (via
+/// [`ConstantBag::make_constant`]) to get the lookup key; only the first
+/// occurrence of a value is added to the pool, and later equal values get
+/// that slot back from a hash-map lookup.
+pub struct ConstantPoolBuilder<B: ConstantBag<Constant = ConstantData>> {
+    bag: B,
+    indices: HashMap<ConstantData, u32>,
+    pool: Vec<ConstantData>,
+}
+
+impl<B: ConstantBag<Constant = ConstantData>> ConstantPoolBuilder<B> {
+    pub fn new(bag: B) -> Self {
+        Self {
+            bag,
+            indices: HashMap::new(),
+            pool: Vec::new(),
+        }
+    }
+
+    /// Intern `constant`, recursing into `Tuple`/`Code` so nested constants
+    /// share the same pool, and return its stable index.
+    pub fn intern<C: Constant>(&mut self, constant: BorrowedConstant<'_, C>) -> u32 {
+        // Recurse first: a `Tuple`/`Code` is only ever looked up by exact
+        // value once fully materialized, so its elements must already be
+        // pool members before we can dedup the container itself.
+        let owned = match constant {
+            BorrowedConstant::Tuple { elements } => ConstantData::Tuple {
+                elements: elements
+                    .iter()
+                    .map(|c| {
+                        let idx = self.intern(c.borrow_constant());
+                        self.pool[idx as usize].clone()
+                    })
+                    .collect(),
+            },
+            BorrowedConstant::Code { code } => {
+                for c in &*code.constants {
+                    self.intern(c.borrow_constant());
+                }
+                self.bag.make_constant(BorrowedConstant::Code { code })
+            }
+            other => self.bag.make_constant(other),
+        };
+        if let Some(&idx) = self.indices.get(&owned) {
+            return idx;
+        }
+        let idx = self.pool.len() as u32;
+        self.pool.push(owned.clone());
+        self.indices.insert(owned, idx);
+        idx
+    }
+
+    /// Finish building, returning the pool in index order.
+    pub fn into_pool(self) -> Vec<ConstantData> {
+        self.pool
+    }
+}
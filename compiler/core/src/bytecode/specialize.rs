@@ -0,0 +1,315 @@
+//! Adaptive-specialization counters (PEP 659 "quickening").
+//!
+//! CPython rewrites a hot, monomorphic instruction in place to a
+//! specialized form that skips its generic dispatch and re-validates a
+//! cheap guard on every execution; a miss deoptimizes back to the generic
+//! opcode and backs off the retry counter so polymorphic call sites stop
+//! thrashing between specializations.
+//!
+//! This tree's [`Instruction`](super::Instruction) table (`instructions.in`)
+//! has no specialized-variant family to rewrite into -- every mnemonic is
+//! already the generic form, so there's nothing for a `specialize` pass to
+//! produce a quickened opcode *to*. [`AdaptiveCounter`] is the
+//! self-contained half of the mechanism that doesn't depend on those
+//! variants existing: the hit-counting and backoff policy a future
+//! specializer would drive once the specialized opcodes land.
+//!
+//! `Instruction::is_adaptive`, [`Instruction::family`] and
+//! [`Instruction::deopt`] mirror the three predicates a real `specialize()`
+//! routine would consult, restricted to what's true in *this* table.
+//! `is_adaptive` is generated from `instructions.in`'s `adaptive` column
+//! (see `build.rs`) rather than hand-matched here, so the set can't drift
+//! out of sync with the table the way a parallel hand-written match could;
+//! `family`/`deopt` stay hand-written below because the table has no
+//! specialized-sibling column to generate them from -- every adaptive
+//! instruction here is its own family head and its own deopt target, i.e.
+//! the identity, not a real deoptimization. A [`QuickenSite`] pairs one of
+//! those instructions with the [`AdaptiveCounter`] that would gate
+//! rewriting it once specialized variants exist to rewrite it *to*.
+//!
+//! [`Specializer`] is the warm-up/guard/deopt state machine a real
+//! `LoadAttr` -> `LoadAttrInstanceValue`/`LoadAttrSlot`/`LoadAttrMethodWithValues`,
+//! `ToBool` -> `ToBoolInt`/`ToBoolBool`/..., or `LoadGlobal` ->
+//! `LoadGlobalModule` quickening pass would drive -- but none of those
+//! specialized mnemonics, nor the `Instrumented*` tier-boundary hooks that
+//! would suspend it, exist in `instructions.in`, so it stops at reporting
+//! warm/hit/deopt transitions rather than ever rewriting an opcode.
+
+/// Per-instruction adaptive counter, modeled on CPython's `_Py_BackoffCounter`:
+/// counts down to zero before a site is considered for specialization, then
+/// backs off exponentially after a deoptimization so a polymorphic site
+/// stops retrying every single execution.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AdaptiveCounter {
+    value: u8,
+    threshold: u8,
+}
+
+impl AdaptiveCounter {
+    /// CPython's initial "cold" value before a site has ever specialized.
+    pub const INITIAL: Self = Self {
+        value: 16,
+        threshold: 16,
+    };
+
+    /// One tick of execution through the generic form. Returns `true` once
+    /// the counter reaches zero, meaning this site is now due to attempt
+    /// specialization.
+    #[inline]
+    pub const fn tick(self) -> (Self, bool) {
+        match self.value.checked_sub(1) {
+            Some(value) => (
+                Self {
+                    value,
+                    threshold: self.threshold,
+                },
+                value == 0,
+            ),
+            None => (self, true),
+        }
+    }
+
+    /// Reset after a deoptimization, doubling the previous threshold (up to
+    /// a cap) so a site that specialized wrongly waits longer before trying
+    /// again.
+    #[inline]
+    pub const fn backoff(self) -> Self {
+        const MAX: u8 = 255;
+        let threshold = self.threshold.saturating_mul(2).min(MAX);
+        Self {
+            value: threshold,
+            threshold,
+        }
+    }
+}
+
+impl Default for AdaptiveCounter {
+    fn default() -> Self {
+        Self::INITIAL
+    }
+}
+
+impl super::Instruction {
+    /// The generic instruction a specialized variant deoptimizes back to.
+    /// No specialized variants exist in this table, so every instruction is
+    /// already its own family head.
+    #[inline]
+    pub const fn family(self) -> Self {
+        self
+    }
+
+    /// Inverse of a real `specialize()`: the generic form to fall back to
+    /// after a guard failure. Identity for the same reason as
+    /// [`Self::family`].
+    #[inline]
+    pub const fn deopt(self) -> Self {
+        self
+    }
+}
+
+/// An adaptive call site: the instruction being watched plus the counter
+/// gating when it's next considered for specialization.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct QuickenSite {
+    instruction: super::Instruction,
+    counter: AdaptiveCounter,
+}
+
+impl QuickenSite {
+    /// Start watching `instruction` at [`AdaptiveCounter::INITIAL`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instruction` isn't [adaptive](super::Instruction::is_adaptive).
+    pub fn new(instruction: super::Instruction) -> Self {
+        assert!(instruction.is_adaptive(), "{instruction:?} is never specialized");
+        Self {
+            instruction,
+            counter: AdaptiveCounter::INITIAL,
+        }
+    }
+
+    /// The instruction this site is watching.
+    #[inline]
+    pub const fn instruction(&self) -> super::Instruction {
+        self.instruction
+    }
+
+    /// Tick the counter for one execution of the generic form. Returns
+    /// `true` once the site is due to attempt specialization -- which, in
+    /// this table, has nothing to rewrite to, so callers can only note the
+    /// site ran hot.
+    pub fn tick(&mut self) -> bool {
+        let (next, due) = self.counter.tick();
+        self.counter = next;
+        due
+    }
+
+    /// Record a deoptimization back to the generic form, backing off the
+    /// counter.
+    pub fn record_deopt(&mut self) {
+        self.counter = self.counter.backoff();
+    }
+}
+
+/// What a [`Specializer::observe`] call decided to do for one execution of
+/// its site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecializeDecision<K> {
+    /// Still warming up (or instrumented): execute the generic form.
+    Unspecialized,
+    /// The site just crossed its warm-up threshold with a monomorphic
+    /// operand kind. In a table with a specialized family this is where the
+    /// caller would rewrite the opcode in place to the variant matching `K`;
+    /// here there's nothing to rewrite it to, so this only marks the site as
+    /// hot.
+    Quicken(K),
+    /// Already specialized, and this execution's operand kind matched the
+    /// guard: take the specialized fast path.
+    Hit,
+    /// Already specialized, but this execution's operand kind didn't match
+    /// the guard: deoptimize back to the generic form and back off the
+    /// counter so a polymorphic site stops retrying every call.
+    Deoptimize,
+}
+
+/// A per-call-site adaptive specialization state machine, generic over `K`,
+/// the kind of runtime-observed operand shape (e.g. "small int", "instance
+/// with a dict at this offset") the specialized guard checks. This crate has
+/// no runtime value representation of its own to supply a concrete `K` --
+/// the eventual VM-side caller picks one -- and no specialized-variant
+/// family to quicken [`Self::instruction`] into, so [`Self::observe`] stops
+/// at reporting *that* a site went hot or cold rather than rewriting any
+/// bytecode.
+///
+/// Specialization must stand down whenever a monitoring tool is watching
+/// this site -- CPython routes an instrumented instruction through its own
+/// tier instead of quickening underneath it (see
+/// [`monitoring`](super::monitoring)) -- so callers pass `instrumented`
+/// computed from whether [`ToolRegistry::active_events`](super::monitoring::ToolRegistry::active_events)
+/// is non-empty for this offset's event kind.
+#[derive(Debug, Clone)]
+pub struct Specializer<K> {
+    site: QuickenSite,
+    quickened: Option<K>,
+}
+
+impl<K: Copy + PartialEq> Specializer<K> {
+    /// Start watching `instruction` at [`AdaptiveCounter::INITIAL`], unspecialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instruction` isn't [adaptive](super::Instruction::is_adaptive).
+    pub fn new(instruction: super::Instruction) -> Self {
+        Self {
+            site: QuickenSite::new(instruction),
+            quickened: None,
+        }
+    }
+
+    /// The instruction this site is watching.
+    #[inline]
+    pub const fn instruction(&self) -> super::Instruction {
+        self.site.instruction()
+    }
+
+    /// Whether this site is currently quickened (has an active guard).
+    #[inline]
+    pub const fn is_specialized(&self) -> bool {
+        self.quickened.is_some()
+    }
+
+    /// Feed one execution's observed operand kind through the state machine.
+    pub fn observe(&mut self, kind: K, instrumented: bool) -> SpecializeDecision<K> {
+        if instrumented {
+            return SpecializeDecision::Unspecialized;
+        }
+        match self.quickened {
+            None => {
+                if self.site.tick() {
+                    self.quickened = Some(kind);
+                    SpecializeDecision::Quicken(kind)
+                } else {
+                    SpecializeDecision::Unspecialized
+                }
+            }
+            Some(expected) if expected == kind => SpecializeDecision::Hit,
+            Some(_) => {
+                self.site.record_deopt();
+                self.quickened = None;
+                SpecializeDecision::Deoptimize
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bytecode::Instruction;
+
+    #[test]
+    fn adaptive_instructions_are_their_own_family_and_deopt() {
+        for instr in [
+            Instruction::BinaryOp,
+            Instruction::CompareOp,
+            Instruction::ToBool,
+            Instruction::ForIter,
+            Instruction::Send,
+        ] {
+            assert_eq!(instr.family(), instr);
+            assert_eq!(instr.deopt(), instr);
+        }
+    }
+
+    #[test]
+    fn quicken_site_ticks_down_and_backs_off_on_deopt() {
+        let mut site = QuickenSite::new(Instruction::BinaryOp);
+        assert_eq!(site.instruction(), Instruction::BinaryOp);
+        let mut due = false;
+        for _ in 0..16 {
+            due = site.tick();
+        }
+        assert!(due);
+
+        site.record_deopt();
+        assert_eq!(site.counter, AdaptiveCounter::INITIAL.backoff());
+    }
+
+    #[test]
+    #[should_panic]
+    fn quicken_site_rejects_non_adaptive_instructions() {
+        QuickenSite::new(Instruction::LoadFast);
+    }
+
+    #[test]
+    fn specializer_quickens_after_warm_up_then_hits_on_matching_kind() {
+        let mut spec = Specializer::new(Instruction::BinaryOp);
+        for _ in 0..15 {
+            assert_eq!(spec.observe("int", false), SpecializeDecision::Unspecialized);
+        }
+        assert_eq!(spec.observe("int", false), SpecializeDecision::Quicken("int"));
+        assert!(spec.is_specialized());
+        assert_eq!(spec.observe("int", false), SpecializeDecision::Hit);
+    }
+
+    #[test]
+    fn specializer_deoptimizes_on_a_mismatched_kind() {
+        let mut spec = Specializer::new(Instruction::BinaryOp);
+        for _ in 0..16 {
+            spec.observe("int", false);
+        }
+        assert!(spec.is_specialized());
+        assert_eq!(spec.observe("str", false), SpecializeDecision::Deoptimize);
+        assert!(!spec.is_specialized());
+    }
+
+    #[test]
+    fn specializer_stands_down_while_instrumented() {
+        let mut spec = Specializer::new(Instruction::BinaryOp);
+        for _ in 0..20 {
+            assert_eq!(spec.observe("int", true), SpecializeDecision::Unspecialized);
+        }
+        assert!(!spec.is_specialized());
+    }
+}
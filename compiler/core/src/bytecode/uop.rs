@@ -0,0 +1,121 @@
+//! A Tier-2 micro-op IR below [`Instruction`], and a straight-line trace
+//! projector over a decoded instruction stream.
+//!
+//! CPython's Tier-2 optimizer breaks each (possibly specialized) Tier-1
+//! opcode into a sequence of guard/action micro-ops, so a trace can fold or
+//! hoist a guard that several consecutive macro-ops would otherwise repeat.
+//! This tree's `instructions.in` table has no specialized-opcode family
+//! (see `specialize.rs`), so there's nothing to split most mnemonics into;
+//! [`Instruction::expand_uops`] therefore passes almost everything through
+//! as a single [`MicroOp::Generic`], except `BinaryOp` and the
+//! scope-exiting return opcodes, which are given the guard/frame-exit
+//! sequences this request asks for as worked examples.
+
+use super::{CodeObject, Constant, Instruction, Label, OpArgState};
+
+/// A single Tier-2 micro-op.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MicroOp {
+    /// Passthrough for an instruction that isn't split further.
+    Generic(Instruction, u32),
+    GuardBothInt,
+    BinaryOpAddInt,
+    PopFrame,
+    /// Marks the original-bytecode offset a guard failure should resume
+    /// interpretation from.
+    SetIp(u32),
+    ExitTrace,
+}
+
+impl MicroOp {
+    /// Whether this uop is a guard (a trace-invalidating check) rather
+    /// than an action.
+    #[inline]
+    pub const fn is_guard(&self) -> bool {
+        matches!(self, Self::GuardBothInt)
+    }
+}
+
+impl Instruction {
+    /// Expand this instruction into its Tier-2 micro-op sequence.
+    pub fn expand_uops(self, oparg: u32) -> Vec<MicroOp> {
+        match self {
+            Self::BinaryOp => vec![MicroOp::GuardBothInt, MicroOp::BinaryOpAddInt],
+            Self::ReturnValue | Self::ReturnConst => {
+                vec![MicroOp::PopFrame, MicroOp::ExitTrace]
+            }
+            other => vec![MicroOp::Generic(other, oparg)],
+        }
+    }
+}
+
+/// Project a bounded straight-line trace starting at `start`, following
+/// only the fall-through edge (the "most likely" path for a loop body with
+/// no further profiling data in this tree), inserting a [`MicroOp::SetIp`]
+/// at every original-instruction boundary and ending in
+/// [`MicroOp::ExitTrace`] once `max_instructions` is reached or a
+/// terminator (an instruction with no fall-through, i.e. an unconditional
+/// jump or a `Return*`) is hit.
+pub fn project_trace<C: Constant>(
+    code: &CodeObject<C>,
+    start: Label,
+    max_instructions: usize,
+) -> Vec<MicroOp> {
+    let mut trace = Vec::new();
+    let mut state = OpArgState::default();
+    let mut offset = 0u32;
+    let mut seen = 0usize;
+
+    for &unit in &code.instructions {
+        let unit_offset = offset;
+        offset += 1;
+        let (instr, arg) = state.get(unit);
+        if instr == Instruction::ExtendedArg {
+            continue;
+        }
+        if unit_offset < start.0 {
+            continue;
+        }
+        if seen >= max_instructions {
+            break;
+        }
+        seen += 1;
+
+        trace.push(MicroOp::SetIp(unit_offset));
+        trace.extend(instr.expand_uops(arg.as_u32()));
+
+        if matches!(instr, Instruction::Jump | Instruction::ReturnValue | Instruction::ReturnConst) {
+            break;
+        }
+    }
+    trace.push(MicroOp::ExitTrace);
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_op_expands_to_guard_then_add() {
+        assert_eq!(
+            Instruction::BinaryOp.expand_uops(0),
+            vec![MicroOp::GuardBothInt, MicroOp::BinaryOpAddInt]
+        );
+    }
+
+    #[test]
+    fn generic_instruction_passes_through() {
+        assert_eq!(
+            Instruction::LoadFast.expand_uops(2),
+            vec![MicroOp::Generic(Instruction::LoadFast, 2)]
+        );
+    }
+
+    #[test]
+    fn guard_predicate_identifies_guards() {
+        assert!(MicroOp::GuardBothInt.is_guard());
+        assert!(!MicroOp::BinaryOpAddInt.is_guard());
+        assert!(!MicroOp::ExitTrace.is_guard());
+    }
+}
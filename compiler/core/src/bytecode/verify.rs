@@ -0,0 +1,296 @@
+//! Static verification of a [`CodeObject`] before it reaches the VM or is
+//! trusted by a fuzzer/assembler.
+//!
+//! [`CodeObject::verify`] independently recomputes the stack-depth fixpoint
+//! CPython's compiler uses: starting from offset `0` at depth `0`, it walks
+//! every reachable instruction's successors (fall-through plus any
+//! [`Instruction::label_arg`] target), propagating `entry_depth +
+//! stack_effect`. Two paths reaching the same offset with different depths,
+//! a depth that goes negative, or a computed maximum above the stored
+//! `max_stackdepth` are all rejected; on success the recomputed maximum is
+//! returned so a caller building a frame from verified bytecode doesn't have
+//! to trust the stored `max_stackdepth` it just cross-checked. It also checks
+//! that every jump target lands on a real instruction boundary rather than
+//! inside an `ExtendedArg` prefix sequence, and that the exception table is
+//! in range and properly nested.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::exceptions::decode_exception_table;
+use super::{CodeObject, Constant, Instruction, Label, OpArgState, Oparg, OpargFamily};
+
+/// A defect found while verifying a [`CodeObject`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// `offset` is reachable with two different stack depths.
+    ConflictingStackDepth { offset: u32, seen: i32, new: i32 },
+    /// The stack depth would go negative at `offset`.
+    NegativeStackDepth { offset: u32 },
+    /// The recomputed maximum exceeds the code object's declared value.
+    StackDepthExceedsDeclared { computed: u32, declared: u32 },
+    /// A jump targets a position that is not the start of a real instruction.
+    LabelIntoExtendedArg { label: Label },
+    /// `exceptiontable` is not a whole number of entries.
+    MalformedExceptionTable,
+    /// An exception-table entry's start/end/handler is out of range.
+    ExceptionEntryOutOfRange { start: u32, end: u32, handler: u32 },
+    /// Exception-table entry `index` neither nests inside nor is disjoint
+    /// from an earlier entry.
+    ExceptionEntryNotNested { index: usize },
+    /// `opcode`'s oparg at `offset` doesn't decode to a valid member of its
+    /// [`OpargFamily`] (an unmapped enum discriminant, or bits outside every
+    /// declared bitflag).
+    InvalidOperand {
+        offset: u32,
+        opcode: Instruction,
+        raw_oparg: u32,
+    },
+}
+
+/// One decoded instruction plus the code-unit offset and size (in code units,
+/// including any `ExtendedArg` prefixes) it occupies.
+struct Decoded {
+    offset: u32,
+    size: u32,
+    instr: Instruction,
+    arg: Oparg,
+}
+
+fn decode<C: Constant>(code: &CodeObject<C>) -> Vec<Decoded> {
+    let mut out = Vec::new();
+    let mut state = OpArgState::default();
+    let mut seq_start = 0u32;
+    for (idx, &unit) in code.instructions.iter().enumerate() {
+        let idx = idx as u32;
+        let (instr, arg) = state.get(unit);
+        if instr == Instruction::ExtendedArg {
+            continue;
+        }
+        out.push(Decoded {
+            offset: seq_start,
+            size: arg.instr_size() as u32,
+            instr,
+            arg,
+        });
+        seq_start = idx + 1;
+    }
+    out
+}
+
+const fn is_unconditional_exit(instr: Instruction) -> bool {
+    matches!(
+        instr,
+        Instruction::Jump { .. } | Instruction::ReturnValue | Instruction::ReturnConst { .. }
+    )
+}
+
+impl<C: Constant> CodeObject<C> {
+    /// Statically validate this code object, returning the first defect
+    /// found rather than risking undefined behaviour in the VM. On success,
+    /// returns the stack depth recomputed by abstract interpretation (the
+    /// same value checked against `self.max_stackdepth`), so a caller
+    /// deserializing or hand-assembling a `CodeObject` can allocate its
+    /// frame off a verified number rather than trusting the stored one.
+    pub fn verify(&self) -> Result<u32, VerifyError> {
+        let decoded = decode(self);
+        self.verify_label_targets(&decoded)?;
+        verify_operands(&decoded)?;
+        let computed = verify_stack_depth(&decoded, self.max_stackdepth)?;
+        verify_exception_table(&self.exceptiontable, self.instructions.len() as u32)?;
+        Ok(computed)
+    }
+
+    /// Every label in [`CodeObject::label_targets`] must point at the start
+    /// of a real instruction, not into the middle of an `ExtendedArg` prefix
+    /// sequence.
+    fn verify_label_targets(&self, decoded: &[Decoded]) -> Result<(), VerifyError> {
+        let boundaries: BTreeSet<u32> = decoded.iter().map(|d| d.offset).collect();
+        for label in self.label_targets() {
+            let Label(offset) = label;
+            if !boundaries.contains(&offset) {
+                return Err(VerifyError::LabelIntoExtendedArg { label });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn verify_stack_depth(decoded: &[Decoded], declared_max: u32) -> Result<u32, VerifyError> {
+    let by_offset: HashMap<u32, usize> = decoded.iter().enumerate().map(|(i, d)| (d.offset, i)).collect();
+
+    let mut seen_depth: HashMap<u32, i32> = HashMap::new();
+    let mut worklist = vec![(0u32, 0i32)];
+    let mut max_depth = 0i32;
+
+    while let Some((offset, entry_depth)) = worklist.pop() {
+        if let Some(&seen) = seen_depth.get(&offset) {
+            if seen != entry_depth {
+                return Err(VerifyError::ConflictingStackDepth {
+                    offset,
+                    seen,
+                    new: entry_depth,
+                });
+            }
+            continue;
+        }
+        if entry_depth < 0 {
+            return Err(VerifyError::NegativeStackDepth { offset });
+        }
+        seen_depth.insert(offset, entry_depth);
+        max_depth = max_depth.max(entry_depth);
+
+        // Jump targets were already checked by `verify_label_targets`; the
+        // one offset missing from `by_offset` that still reaches here is the
+        // fall-through past the last instruction, which has no successor.
+        let Some(&idx) = by_offset.get(&offset) else {
+            continue;
+        };
+        let d = &decoded[idx];
+
+        if !is_unconditional_exit(d.instr) {
+            let fallthrough_depth = entry_depth + d.instr.stack_effect(d.arg.as_u32(), false);
+            if fallthrough_depth < 0 {
+                return Err(VerifyError::NegativeStackDepth { offset: d.offset });
+            }
+            worklist.push((d.offset + d.size, fallthrough_depth));
+        }
+        if let Some(target) = d.instr.label_arg() {
+            let jump_depth = entry_depth + d.instr.stack_effect(d.arg.as_u32(), true);
+            if jump_depth < 0 {
+                return Err(VerifyError::NegativeStackDepth { offset: d.offset });
+            }
+            let Label(target_offset) = target;
+            worklist.push((target_offset, jump_depth));
+        }
+    }
+
+    let computed = max_depth as u32;
+    if computed > declared_max {
+        return Err(VerifyError::StackDepthExceedsDeclared {
+            computed,
+            declared: declared_max,
+        });
+    }
+    Ok(computed)
+}
+
+/// Check that every instruction's raw oparg decodes to a valid
+/// [`OpargFamily`] member, catching the out-of-range discriminants and
+/// reserved bit patterns `MarshalError::InvalidBytecode` would otherwise
+/// only surface lazily mid-execution.
+fn verify_operands(decoded: &[Decoded]) -> Result<(), VerifyError> {
+    for d in decoded {
+        if OpargFamily::decode(d.instr, d.arg).is_err() {
+            return Err(VerifyError::InvalidOperand {
+                offset: d.offset,
+                opcode: d.instr,
+                raw_oparg: d.arg.as_u32(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn verify_exception_table(bytes: &[u8], len: u32) -> Result<(), VerifyError> {
+    let entries = decode_exception_table(bytes).map_err(|_| VerifyError::MalformedExceptionTable)?;
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.start >= entry.end || entry.end > len || entry.handler >= len {
+            return Err(VerifyError::ExceptionEntryOutOfRange {
+                start: entry.start,
+                end: entry.end,
+                handler: entry.handler,
+            });
+        }
+        for other in &entries[..i] {
+            let disjoint = entry.end <= other.start || entry.start >= other.end;
+            let nested = (other.start <= entry.start && entry.end <= other.end)
+                || (entry.start <= other.start && other.end <= entry.end);
+            if !disjoint && !nested {
+                return Err(VerifyError::ExceptionEntryNotNested { index: i });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        BinaryOperatorOparg, CompareOperandOparg, FormatValueConversion, FormatValueSpec,
+        IntrinsicFunction1Oparg, IntrinsicFunction2Oparg, MakeFunctionFlags, OpargFamilyMember,
+        ResumeOparg,
+    };
+    use super::*;
+
+    /// Every `(opcode, raw oparg)` pair exhaustively over `u8`: the
+    /// verifier's accept/reject decision must exactly match what
+    /// `try_from_u8`/the bitflags masks would independently conclude, so an
+    /// enum discriminant added without a matching verifier update is caught
+    /// immediately rather than by chance.
+    fn decode_accepts(opcode: Instruction, raw: u8) -> bool {
+        OpargFamily::decode(opcode, Oparg::new(raw as u32)).is_ok()
+    }
+
+    #[test]
+    fn resume_oparg_matches_try_from_u8() {
+        for raw in 0..=u8::MAX {
+            assert_eq!(
+                decode_accepts(Instruction::Resume, raw),
+                ResumeOparg::try_from_u8(raw).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn binary_operator_oparg_matches_try_from_u8() {
+        for raw in 0..=u8::MAX {
+            assert_eq!(
+                decode_accepts(Instruction::BinaryOp, raw),
+                BinaryOperatorOparg::try_from_u8(raw).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn intrinsic_function_opargs_match_try_from_u8() {
+        for raw in 0..=u8::MAX {
+            assert_eq!(
+                decode_accepts(Instruction::CallIntrinsic1, raw),
+                IntrinsicFunction1Oparg::try_from_u8(raw).is_ok()
+            );
+            assert_eq!(
+                decode_accepts(Instruction::CallIntrinsic2, raw),
+                IntrinsicFunction2Oparg::try_from_u8(raw).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn make_function_flags_match_bitflags_mask() {
+        for raw in 0..=u8::MAX {
+            assert_eq!(
+                decode_accepts(Instruction::MakeFunction, raw),
+                MakeFunctionFlags::from_bits(raw).is_some()
+            );
+        }
+    }
+
+    #[test]
+    fn compare_op_oparg_matches_try_from_u8() {
+        for raw in 0..=u8::MAX {
+            assert_eq!(
+                decode_accepts(Instruction::CompareOp, raw),
+                CompareOperandOparg::try_from_u8(raw).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn format_value_matches_conversion_and_spec_masks() {
+        for raw in 0..=u8::MAX {
+            let expected = FormatValueConversion::from_bits(raw & !FormatValueSpec::MASK.bits()).is_some()
+                && FormatValueSpec::from_bits(raw & FormatValueSpec::MASK.bits()).is_some();
+            assert_eq!(decode_accepts(Instruction::FormatValue, raw), expected);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! A compact two-byte-per-unit ("wordcode") encoding of an [`Instruction`]
+//! stream -- the same `opcode byte + arg byte` shape [`CodeObject`]'s own
+//! `instructions: Box<[CodeUnit]>` already stores, exposed as a standalone
+//! `Instruction`-stream <-> `Vec<u8>` pair so a caller assembling or
+//! rewriting instructions doesn't need a whole `CodeObject` to round-trip
+//! through one. [`cache`](super::cache) already has the on-disk container
+//! (magic/version header, LEB128-packed fields) for a full code object and
+//! packs each code unit's fields itself; this is a narrower byte-for-byte
+//! layer beside it -- and underneath
+//! `specialize.rs`'s eventual in-place rewriting, since a two-byte wordcode
+//! unit makes "replace this instruction" a fixed-offset one-byte store.
+//! [`Instruction::encode_into`]/[`decode_at`] are the single-instruction
+//! encode/decode halves for a PC-driven eval loop; [`encode`]/[`decode`]
+//! wrap them for whole-buffer round-tripping.
+//!
+//! This table has no separate `PseudoInstruction` family reachable from
+//! here to special-case: every mnemonic [`Instruction`] already names is a
+//! real, encodable opcode, so there's nothing pre-assembly-only for these
+//! functions to reject.
+//!
+//! [`CodeObject`]: super::CodeObject
+
+use super::{CodeUnit, Instruction, OpArgByte, OpArgState, Oparg};
+
+/// A failure decoding a wordcode byte stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordcodeError {
+    /// The byte stream wasn't a whole number of 2-byte units.
+    Truncated,
+    /// A byte didn't name any [`Instruction`] variant.
+    UnknownOpcode(u8),
+}
+
+impl Instruction {
+    /// Append this instruction's wordcode encoding to `out`: any
+    /// `ExtendedArg` prefix units its `oparg` needs, then its own opcode
+    /// byte and low arg byte. The encode half of the PC-indexed
+    /// [`decode_at`].
+    pub fn encode_into(self, oparg: u32, out: &mut Vec<u8>) {
+        let (ext, lo) = Oparg::new(oparg).split();
+        for byte in ext {
+            out.push(u8::from(Instruction::ExtendedArg));
+            out.push(*byte);
+        }
+        out.push(u8::from(self));
+        out.push(*lo);
+    }
+}
+
+/// Encode `(instruction, oparg)` pairs into their wordcode bytes, emitting
+/// `ExtendedArg` prefix units ahead of any instruction whose oparg doesn't
+/// fit in a single byte.
+pub fn encode(instrs: &[(Instruction, u32)]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(instrs.len() * 2);
+    for &(instr, raw_arg) in instrs {
+        instr.encode_into(raw_arg, &mut out);
+    }
+    out
+}
+
+/// Decode the single instruction starting at byte offset `pc` (folding in
+/// any `ExtendedArg` prefix units immediately before it), returning the
+/// decoded `(instruction, oparg)` and the `pc` of the next instruction. The
+/// decode half of [`Instruction::encode_into`], for an eval loop that fetches
+/// one instruction at a time rather than decoding a whole buffer up front.
+pub fn decode_at(bytes: &[u8], pc: usize) -> Result<(Instruction, u32, usize), WordcodeError> {
+    let mut state = OpArgState::default();
+    let mut pc = pc;
+    loop {
+        let op_byte = *bytes.get(pc).ok_or(WordcodeError::Truncated)?;
+        let arg_byte = *bytes.get(pc + 1).ok_or(WordcodeError::Truncated)?;
+        let op = Instruction::try_from(op_byte).map_err(|_| WordcodeError::UnknownOpcode(op_byte))?;
+        let unit = CodeUnit::new(op, OpArgByte::new(arg_byte));
+        let (instr, arg) = state.get(unit);
+        pc += 2;
+        if instr != Instruction::ExtendedArg {
+            return Ok((instr, arg.as_u32(), pc));
+        }
+    }
+}
+
+/// The inverse of [`encode`]: decode wordcode bytes back into one
+/// `(instruction, oparg)` pair per real instruction, folding any
+/// `ExtendedArg` prefixes into the oparg they widen rather than yielding
+/// them as entries of their own.
+pub fn decode(bytes: &[u8]) -> Result<Vec<(Instruction, u32)>, WordcodeError> {
+    if bytes.len() % 2 != 0 {
+        return Err(WordcodeError::Truncated);
+    }
+    let mut out = Vec::new();
+    let mut pc = 0;
+    while pc < bytes.len() {
+        let (instr, arg, next_pc) = decode_at(bytes, pc)?;
+        out.push((instr, arg));
+        pc = next_pc;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_single_byte_opargs() {
+        let instrs = [
+            (Instruction::LoadFast, 2u32),
+            (Instruction::LoadConst, 0),
+            (Instruction::ReturnValue, 0),
+        ];
+        let bytes = encode(&instrs);
+        assert_eq!(bytes.len(), instrs.len() * 2);
+        assert_eq!(decode(&bytes).unwrap(), instrs);
+    }
+
+    #[test]
+    fn wide_oparg_round_trips_through_extended_arg_prefixes() {
+        let instrs = [(Instruction::LoadConst, 0x1_2345u32)];
+        let bytes = encode(&instrs);
+        // Two ExtendedArg prefix units (0x01, 0x23) plus the real unit (0x45).
+        assert_eq!(bytes.len(), 6);
+        assert_eq!(decode(&bytes).unwrap(), instrs);
+    }
+
+    #[test]
+    fn rejects_truncated_stream() {
+        assert_eq!(decode(&[0u8]), Err(WordcodeError::Truncated));
+    }
+
+    #[test]
+    fn rejects_unknown_opcode_byte() {
+        assert_eq!(decode(&[0xff, 0x00]), Err(WordcodeError::UnknownOpcode(0xff)));
+    }
+
+    #[test]
+    fn decode_at_steps_the_pc_past_extended_arg_prefixes() {
+        let bytes = encode(&[(Instruction::LoadConst, 0x1_2345), (Instruction::PopTop, 0)]);
+        let (instr, arg, pc) = decode_at(&bytes, 0).unwrap();
+        assert_eq!((instr, arg), (Instruction::LoadConst, 0x1_2345));
+        assert_eq!(pc, 6);
+        let (instr, arg, pc) = decode_at(&bytes, pc).unwrap();
+        assert_eq!((instr, arg), (Instruction::PopTop, 0));
+        assert_eq!(pc, bytes.len());
+    }
+}
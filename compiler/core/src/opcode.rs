@@ -1,3 +1,11 @@
+// `LOAD_METHOD`/`LOAD_SUPER_METHOD`/`LOAD_ZERO_SUPER_METHOD`/
+// `LOAD_ZERO_SUPER_ATTR` belong as variants on `PseudoOpcode`, defined in
+// `crate::opcodes` below -- that module isn't vendored in this snapshot, so
+// they can't be added from this crate without inventing the rest of its
+// opcode table. Tracked here rather than silently dropped; add the
+// variants (plus their `has_name`/numbering) in `crate::opcodes` first,
+// then extend `PseudoOpcode::lower` in this file to resolve them to
+// `LoadAttr`/`LoadSuperAttr`.
 pub use crate::opcodes::{PseudoOpcode, RealOpcode};
 
 macro_rules! gen_has_attr_fn {
@@ -116,6 +124,69 @@ impl Opcode {
     gen_has_attr_fn!(has_free);
     gen_has_attr_fn!(has_jump);
     gen_has_attr_fn!(has_local);
+
+    /// The generic form of this opcode, obtained by deopting a specialized
+    /// [`RealOpcode`] back to its family base (see [`RealOpcode::deopt`]);
+    /// pseudo-opcodes are never specialized, so they deopt to themselves.
+    #[inline]
+    pub const fn deopt(self) -> Self {
+        match self {
+            Real(val) => Real(val.deopt()),
+            Pseudo(_) => self,
+        }
+    }
+
+    /// The number of trailing `CACHE` code units following an instance of
+    /// this opcode (see [`RealOpcode::inline_cache_entries`]); always `0`
+    /// for pseudo-opcodes, which never reach a finished code object.
+    #[inline]
+    pub const fn inline_cache_entries(&self) -> u32 {
+        match self.real() {
+            Some(val) => val.inline_cache_entries(),
+            None => 0,
+        }
+    }
+
+    /// Whether this opcode is a `CACHE` filler entry rather than a real
+    /// instruction. This tree's [`RealOpcode`] doesn't have a confirmed
+    /// `Cache` variant (the full specialization table it would belong to
+    /// isn't vendored here), so this always returns `false`; once `Cache`
+    /// lands, it belongs in this match.
+    #[inline]
+    pub const fn is_cache(&self) -> bool {
+        false
+    }
+
+    /// Net change in value-stack size from executing this opcode with
+    /// `oparg`, on the fall-through edge (`jump == false`) or the
+    /// taken-jump edge (`jump == true`) -- modeled on CPython's
+    /// `PyCompile_OpcodeStackEffectWithJump`. `None` means this opcode's
+    /// effect isn't modeled here (covers the CPython opcodes actually
+    /// referenced elsewhere in this module plus `stack_effect`'s own
+    /// worked examples; `RealOpcode`'s full specialization family is not
+    /// vendored in this tree, so this intentionally isn't exhaustive).
+    pub const fn stack_effect(&self, oparg: u32, jump: bool) -> Option<i32> {
+        if let Some(real) = self.real() {
+            if !real.is_modeled() {
+                return None;
+            }
+            return Some(real.num_pushed(oparg) as i32 - real.num_popped(oparg, jump) as i32);
+        }
+        if let Some(pseudo) = self.pseudo() {
+            // These leave the exception-handler bookkeeping CPython's
+            // `SETUP_FINALLY`/`SETUP_WITH`/`SETUP_CLEANUP` push onto the
+            // block stack; this tree doesn't model block-stack depth
+            // beyond a single slot, so they're all `+1`.
+            return match pseudo {
+                RealOpcode::SetupFinally | RealOpcode::SetupWith | RealOpcode::SetupCleanup => {
+                    Some(1)
+                }
+                RealOpcode::Jump | RealOpcode::JumpNoInterrupt => Some(0),
+                _ => None,
+            };
+        }
+        None
+    }
 }
 
 macro_rules! impl_try_from {
@@ -137,3 +208,215 @@ macro_rules! impl_try_from {
 impl_try_from!(
     Opcode, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
 );
+
+impl RealOpcode {
+    /// The generic ("unspecialized") form of this opcode, or `self` if it
+    /// isn't a specialized member of an adaptive family -- mirrors
+    /// CPython's `_PyOpcode_Deopt` table.
+    ///
+    /// The full CPython 3.13 specialization families (`LOAD_ATTR_*`,
+    /// `BINARY_OP_*_INT`, `CALL_PY_EXACT_ARGS`, etc.) aren't vendored in
+    /// this tree's [`RealOpcode`], so every variant confirmed to exist
+    /// here is already generic and this is the identity function. Once
+    /// the specialized variants land, their arms belong here.
+    #[inline]
+    pub const fn deopt(self) -> Self {
+        self
+    }
+
+    /// The number of trailing `CACHE` code units that follow an instance
+    /// of this opcode, to be skipped when walking a marshalled
+    /// instruction stream -- mirrors CPython's `_PyOpcode_Caches` table.
+    ///
+    /// Same caveat as [`Self::deopt`]: none of the specialized,
+    /// cache-bearing families are modeled in this tree yet, so every
+    /// confirmed variant here has zero inline-cache entries.
+    #[inline]
+    pub const fn inline_cache_entries(self) -> u32 {
+        0
+    }
+
+    /// Whether [`Self::num_popped`]/[`Self::num_pushed`] have a real arm for
+    /// this opcode, as opposed to falling through to the `0, 0` default.
+    const fn is_modeled(self) -> bool {
+        matches!(
+            self,
+            Self::PopTop
+                | Self::LoadConst
+                | Self::LoadFast
+                | Self::BinaryOp
+                | Self::CompareOp
+                | Self::ReturnValue
+                | Self::ReturnConst
+                | Self::RaiseVarargs
+                | Self::Reraise
+                | Self::JumpForward
+                | Self::JumpBackward
+                | Self::JumpBackwardNoInterrupt
+                | Self::PopJumpIfTrue
+                | Self::PopJumpIfFalse
+                | Self::BuildList
+                | Self::BuildTuple
+                | Self::BuildSet
+                | Self::Call
+                | Self::BuildSlice
+        )
+    }
+
+    /// How many values this opcode pops off the stack, given `oparg` and
+    /// which edge (`jump` taken or not) is being analyzed -- modeled on
+    /// CPython's generated `_PyOpcode_num_popped`.
+    ///
+    /// Only the opcodes confirmed to exist in this tree's [`RealOpcode`]
+    /// are modeled; everything else (including every CPython 3.13
+    /// specialized-family member, since none are vendored here) pops `0`.
+    pub const fn num_popped(self, oparg: u32, jump: bool) -> i32 {
+        match self {
+            Self::PopTop | Self::ReturnValue | Self::Reraise => 1,
+            Self::BinaryOp | Self::CompareOp => 2,
+            Self::RaiseVarargs => oparg as i32,
+            Self::PopJumpIfTrue | Self::PopJumpIfFalse => {
+                // Both edges consume the condition; `jump` only changes
+                // where control resumes, not what's popped.
+                let _ = jump;
+                1
+            }
+            Self::BuildList | Self::BuildTuple | Self::BuildSet | Self::BuildSlice => {
+                oparg as i32
+            }
+            Self::Call => oparg as i32 + 2,
+            _ => 0,
+        }
+    }
+
+    /// How many values this opcode pushes onto the stack, given `oparg` --
+    /// modeled on CPython's generated `_PyOpcode_num_pushed`. Same coverage
+    /// caveat as [`Self::num_popped`].
+    pub const fn num_pushed(self, oparg: u32) -> i32 {
+        let _ = oparg;
+        match self {
+            Self::LoadConst | Self::LoadFast | Self::BuildList | Self::BuildTuple
+            | Self::BuildSet | Self::Call | Self::BuildSlice | Self::BinaryOp
+            | Self::CompareOp => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl PseudoOpcode {
+    /// Pseudo-opcodes are compiler-only placeholders that never survive
+    /// into a finished code object -- always `true`; exists for symmetry
+    /// with [`Opcode::real`]/[`Opcode::pseudo`].
+    #[inline]
+    pub const fn is_pseudo(self) -> bool {
+        true
+    }
+
+    /// Resolve this pseudo-opcode to the real opcode the compiler emits in
+    /// its place, given whether the jump it represents goes backwards.
+    ///
+    /// This tree's [`PseudoOpcode`] only has the five variants confirmed
+    /// in this module (`SetupFinally`/`SetupWith`/`SetupCleanup`/`Jump`/
+    /// `JumpNoInterrupt`); the remaining CPython pseudo ops this request
+    /// names (`POP_BLOCK`, `LOAD_METHOD`, `LOAD_SUPER_METHOD`,
+    /// `LOAD_ZERO_SUPER_METHOD`, `LOAD_ZERO_SUPER_ATTR`,
+    /// `STORE_FAST_MAYBE_NULL`, `LOAD_CLOSURE`) aren't vendored here, and
+    /// `SetupFinally`/`SetupWith`/`SetupCleanup` don't have a confirmed
+    /// real counterpart to lower to, so this only covers the two jump
+    /// pseudo ops and returns `None` for everything else.
+    pub const fn lower(self, backwards: bool) -> Option<RealOpcode> {
+        match self {
+            Self::Jump if backwards => Some(RealOpcode::JumpBackward),
+            Self::Jump => Some(RealOpcode::JumpForward),
+            Self::JumpNoInterrupt if backwards => Some(RealOpcode::JumpBackwardNoInterrupt),
+            Self::JumpNoInterrupt => Some(RealOpcode::JumpForward),
+            _ => None,
+        }
+    }
+}
+
+/// A Tier-2 micro-op, as CPython's trace optimizer would see it: a single
+/// guard check or action, smaller-grained than a Tier-1 [`RealOpcode`].
+///
+/// CPython's real `_PyUOp` table has hundreds of these, one per guard/action
+/// a specialized macro op can split into (`_GUARD_BOTH_INT`,
+/// `_BINARY_OP_ADD_INT`, `_GUARD_TYPE_VERSION`, `_LOAD_ATTR_INSTANCE_VALUE`,
+/// `_POP_FRAME`, ...). None of the specialized [`RealOpcode`] families that
+/// would expand into them are vendored in this tree, so only the handful
+/// this request names as worked examples are modeled; every other opcode
+/// expands to a single `Generic` passthrough uop carrying its own oparg.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Uop {
+    /// Passthrough for a non-specialized opcode: no splitting, no guards.
+    Generic(RealOpcode),
+    GuardBothInt,
+    BinaryOpAddInt,
+    GuardTypeVersion,
+    CheckManagedObjectHasValues,
+    LoadAttrInstanceValue,
+    GuardGlobalsVersion,
+    LoadGlobalModule,
+    PopFrame,
+    SetIp,
+    ExitTrace,
+}
+
+impl Uop {
+    /// Whether this uop is a guard (a trace-invalidating check) rather than
+    /// an action, so a trace optimizer can fold away redundant ones.
+    #[inline]
+    pub const fn is_guard(&self) -> bool {
+        matches!(
+            self,
+            Self::GuardBothInt | Self::GuardTypeVersion | Self::GuardGlobalsVersion
+        )
+    }
+}
+
+/// Expand a Tier-1 opcode into the ordered Tier-2 micro-ops it would trace
+/// to, each paired with its own slice of `oparg`.
+///
+/// Only `RealOpcode::BinaryOp` (standing in for the unvendored
+/// `BinaryOpAddInt` specialization) and the scope-exiting return opcodes
+/// are given their CPython-documented guard/frame-exit sequences, purely
+/// to demonstrate the splitting shape this request describes; every other
+/// opcode returns a single `Generic` passthrough.
+pub fn expand(opcode: RealOpcode, oparg: u32) -> Vec<(Uop, u32)> {
+    match opcode {
+        RealOpcode::BinaryOp => vec![(Uop::GuardBothInt, 0), (Uop::BinaryOpAddInt, oparg)],
+        RealOpcode::ReturnValue | RealOpcode::ReturnConst => {
+            vec![(Uop::PopFrame, 0), (Uop::SetIp, 0), (Uop::ExitTrace, 0)]
+        }
+        other => vec![(Uop::Generic(other), oparg)],
+    }
+}
+
+impl std::str::FromStr for Opcode {
+    type Err = ();
+
+    /// Parse a CPython SCREAMING_SNAKE mnemonic (`"JUMP_FORWARD"`,
+    /// `"RETURN_VALUE"`, `"SETUP_FINALLY"`, ...) back into an `Opcode`.
+    ///
+    /// This crate's real numeric opcode IDs live in `crate::opcodes`,
+    /// which isn't vendored in this snapshot, so `Opcode::as_u8` isn't
+    /// added here -- fabricating byte values without that table would
+    /// make them silently disagree with it once it exists. Only the
+    /// mnemonics for the variants confirmed in this module are covered.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "JUMP_FORWARD" => Real(RealOpcode::JumpForward),
+            "JUMP_BACKWARD" => Real(RealOpcode::JumpBackward),
+            "JUMP_BACKWARD_NO_INTERRUPT" => Real(RealOpcode::JumpBackwardNoInterrupt),
+            "RETURN_VALUE" => Real(RealOpcode::ReturnValue),
+            "RETURN_CONST" => Real(RealOpcode::ReturnConst),
+            "RAISE_VARARGS" => Real(RealOpcode::RaiseVarargs),
+            "RERAISE" => Real(RealOpcode::Reraise),
+            "SETUP_FINALLY" => Pseudo(PseudoOpcode::SetupFinally),
+            "SETUP_WITH" => Pseudo(PseudoOpcode::SetupWith),
+            "SETUP_CLEANUP" => Pseudo(PseudoOpcode::SetupCleanup),
+            "JUMP" => Pseudo(PseudoOpcode::Jump),
+            "JUMP_NO_INTERRUPT" => Pseudo(PseudoOpcode::JumpNoInterrupt),
+            _ => return Err(()),
+        })
+    }
+}
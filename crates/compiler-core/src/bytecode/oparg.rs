@@ -181,3 +181,105 @@ impl OpargState {
         self.state = Oparg::NULL;
     }
 }
+
+/// Streaming encoder: the inverse of [`OpargState`]. Accepts `(RealInstruction,
+/// oparg)` pairs and emits the `CodeUnit`s they decode back from, inserting the
+/// `ExtendedArg` prefixes needed to carry opargs wider than one byte.
+#[derive(Clone, Default)]
+pub struct OpargEncoder {
+    units: Vec<CodeUnit>,
+}
+
+impl OpargEncoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pre-allocate room for `units` code units, e.g. from
+    /// [`required_code_units`].
+    #[must_use]
+    pub fn with_capacity(units: usize) -> Self {
+        Self {
+            units: Vec::with_capacity(units),
+        }
+    }
+
+    /// Emit a single instruction: the `ExtendedArg` prefix units carrying the
+    /// big-endian high bytes, then the real opcode with the low byte.
+    pub fn push(&mut self, instr: RealInstruction, arg: impl Into<Oparg>) {
+        let (ext, lo) = arg.into().split();
+        for byte in ext {
+            self.units.push(CodeUnit {
+                op: RealInstruction::ExtendedArg(byte),
+                arg: byte,
+            });
+        }
+        self.units.push(CodeUnit { op: instr, arg: lo });
+    }
+
+    /// Build an encoder pre-sized from the exact code-unit count of `args` and
+    /// emit them, so a whole code object is serialized with a single allocation.
+    #[must_use]
+    pub fn for_instructions(args: &[(RealInstruction, Oparg)]) -> Self {
+        let mut encoder = Self::with_capacity(required_code_units(args));
+        encoder.extend_from_instructions(args.iter().map(|&(op, arg)| (op, arg)));
+        encoder
+    }
+
+    /// Emit every `(instruction, oparg)` pair in order.
+    pub fn extend_from_instructions<I, A>(&mut self, instrs: I)
+    where
+        I: IntoIterator<Item = (RealInstruction, A)>,
+        A: Into<Oparg>,
+    {
+        for (instr, arg) in instrs {
+            self.push(instr, arg);
+        }
+    }
+
+    /// Consume the encoder and return the encoded stream.
+    #[must_use]
+    pub fn finish(self) -> Vec<CodeUnit> {
+        self.units
+    }
+}
+
+/// Number of `CodeUnit`s a stream of instructions encodes to, counting every
+/// `ExtendedArg` prefix, so the output buffer can be sized exactly up front.
+#[must_use]
+pub fn required_code_units(args: &[(RealInstruction, Oparg)]) -> usize {
+    args.iter().map(|(_, arg)| arg.instr_size()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(instrs: &[(RealInstruction, Oparg)]) {
+        let mut encoder = OpargEncoder::with_capacity(required_code_units(instrs));
+        encoder.extend_from_instructions(instrs.iter().map(|&(op, arg)| (op, arg)));
+        let units = encoder.finish();
+        assert_eq!(units.len(), required_code_units(instrs));
+
+        let mut state = OpargState::default();
+        let mut decoded = Vec::new();
+        for unit in units {
+            let (op, arg) = state.get(unit);
+            if !matches!(op, RealInstruction::ExtendedArg(_)) {
+                decoded.push((op, arg));
+            }
+        }
+        assert_eq!(decoded, instrs);
+    }
+
+    #[test]
+    fn roundtrips_through_oparg_state() {
+        roundtrip(&[
+            (RealInstruction::Nop, Oparg::new(0)),
+            (RealInstruction::Nop, Oparg::new(0xff)),
+            (RealInstruction::Nop, Oparg::new(0x1234)),
+            (RealInstruction::Nop, Oparg::new(0x0100_0001)),
+        ]);
+    }
+}
@@ -413,3 +413,110 @@ any_oparg_enum!(
         AfterAExit = 2,
     }
 );
+
+use crate::RealInstruction;
+
+/// Reserved oparg value that never names a real local/jump slot; decoding it as
+/// one is a corrupt-bytecode error.
+const RESERVED_OPARG: u32 = u32::MAX;
+
+/// Index into a frame's fast-locals array.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct LocalIdx(Oparg);
+
+/// Absolute instruction offset a jump transfers control to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct JumpTarget(Oparg);
+
+/// Signed change a variadic instruction makes to the value stack.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct StackDelta(i32);
+
+impl LocalIdx {
+    #[must_use]
+    pub const fn get(self) -> u32 {
+        self.0.as_u32()
+    }
+}
+
+impl JumpTarget {
+    #[must_use]
+    pub const fn get(self) -> u32 {
+        self.0.as_u32()
+    }
+}
+
+impl StackDelta {
+    #[must_use]
+    pub const fn get(self) -> i32 {
+        self.0
+    }
+}
+
+impl crate::AnyOparg for LocalIdx {
+    fn try_from_oparg(value: Oparg) -> Result<Self, MarshalError> {
+        if value.as_u32() == RESERVED_OPARG {
+            return Err(MarshalError::InvalidBytecode);
+        }
+        Ok(Self(value))
+    }
+
+    fn as_oparg(self) -> Oparg {
+        self.0
+    }
+}
+
+impl crate::AnyOparg for JumpTarget {
+    fn try_from_oparg(value: Oparg) -> Result<Self, MarshalError> {
+        if value.as_u32() == RESERVED_OPARG {
+            return Err(MarshalError::InvalidBytecode);
+        }
+        Ok(Self(value))
+    }
+
+    fn as_oparg(self) -> Oparg {
+        self.0
+    }
+}
+
+impl crate::AnyOparg for StackDelta {
+    fn try_from_oparg(value: Oparg) -> Result<Self, MarshalError> {
+        Ok(Self(value.as_u32() as i32))
+    }
+
+    fn as_oparg(self) -> Oparg {
+        Oparg::new(self.0 as u32)
+    }
+}
+
+/// A fully-typed operand, produced by [`decode_typed`]. Downstream passes match
+/// on this instead of interpreting a bare `u32`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TypedOparg {
+    Local(LocalIdx),
+    Name(NameIdxOparg),
+    Jump(JumpTarget),
+    Compare(CompareOparg),
+    /// Instructions whose argument has no dedicated wrapper yet.
+    Raw(Oparg),
+}
+
+/// Decode `arg` into the operand type expected by `ins`, validating it at the
+/// same time. Unknown opcodes fall through to [`TypedOparg::Raw`].
+pub fn decode_typed(ins: RealInstruction, arg: Oparg) -> Result<TypedOparg, MarshalError> {
+    use crate::AnyOparg;
+    Ok(match ins {
+        RealInstruction::LoadFast(_)
+        | RealInstruction::StoreFast(_)
+        | RealInstruction::DeleteFast(_) => TypedOparg::Local(LocalIdx::try_from_oparg(arg)?),
+        RealInstruction::JumpForward { .. }
+        | RealInstruction::JumpBackward { .. } => TypedOparg::Jump(JumpTarget::try_from_oparg(arg)?),
+        RealInstruction::CompareOp { .. } => {
+            TypedOparg::Compare(CompareOparg::try_from_oparg(arg)?)
+        }
+        _ => TypedOparg::Raw(arg),
+    })
+}
@@ -46,6 +46,42 @@ impl<'a> Database<'a> {
     pub fn try_module_item(&self, module: &str, item: &str) -> Result {
         self.try_path(&format!("{}.{}", module, item))
     }
+
+    /// Return the keys closest to `path` by Levenshtein edit distance, nearest
+    /// first, for building "did you mean…?" hints on a failed lookup.
+    pub fn suggest(&self, path: &str) -> Vec<&'a str> {
+        let threshold = (path.len() / 3).max(1);
+        let mut matches: Vec<(usize, &'a str)> = self
+            .inner
+            .keys()
+            .filter_map(|&key| {
+                let dist = edit_distance(path, key);
+                (dist <= threshold).then_some((dist, key))
+            })
+            .collect();
+        matches.sort_by_key(|&(dist, key)| (dist, key));
+        matches.into_iter().take(3).map(|(_, key)| key).collect()
+    }
+}
+
+/// Levenshtein edit distance via the two-row dynamic-programming recurrence,
+/// kept to a single reused row of length `n + 1`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let n = b.len();
+    let mut row: Vec<usize> = (0..=n).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for j in 0..n {
+            let cur = (row[j] + 1)
+                .min(row[j + 1] + 1)
+                .min(prev + (ca != b[j]) as usize);
+            prev = row[j + 1];
+            row[j + 1] = cur;
+        }
+    }
+    row[n]
 }
 
 #[cfg(test)]
@@ -59,4 +95,12 @@ mod test {
             .unwrap();
         assert!(doc.is_some());
     }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("foo", "foo"), 0);
+        assert_eq!(edit_distance("foo", "fool"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
 }
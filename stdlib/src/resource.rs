@@ -10,6 +10,8 @@ mod resource {
         stdlib::os,
         types::PyStructSequence,
     };
+    use malachite_bigint::BigInt;
+    use num_traits::ToPrimitive;
     use std::{io, mem};
 
     cfg_if::cfg_if! {
@@ -24,13 +26,24 @@ mod resource {
         }
     }
 
-    // TODO: RLIMIT_OFILE,
     #[pyattr]
     use libc::{
         RLIM_INFINITY, RLIMIT_AS, RLIMIT_CORE, RLIMIT_CPU, RLIMIT_DATA, RLIMIT_FSIZE,
         RLIMIT_MEMLOCK, RLIMIT_NOFILE, RLIMIT_NPROC, RLIMIT_RSS, RLIMIT_STACK,
     };
 
+    // CPython only exposes this alias on the BSDs/Solaris, where libc still
+    // defines it (as the same value as `RLIMIT_NOFILE`); elsewhere it simply
+    // doesn't exist, same as upstream.
+    #[cfg(any(
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "solaris",
+        target_os = "illumos"
+    ))]
+    #[pyattr]
+    use libc::RLIMIT_OFILE;
+
     #[cfg(any(target_os = "linux", target_os = "android", target_os = "emscripten"))]
     #[pyattr]
     use libc::{RLIMIT_MSGQUEUE, RLIMIT_NICE, RLIMIT_RTPRIO, RLIMIT_SIGPENDING};
@@ -131,17 +144,45 @@ mod resource {
         })
     }
 
+    /// The system's page size, in bytes -- useful for converting
+    /// page-denominated fields like `ru_nswap` or an `RLIMIT_RSS` value to
+    /// bytes.
+    #[pyfunction]
+    fn getpagesize() -> i64 {
+        cfg_if::cfg_if! {
+            if #[cfg(target_os = "android")] {
+                // `_SC_PAGESIZE` isn't reliably wired up on Android's libc;
+                // `getpagesize()` is the same syscall result under the hood.
+                unsafe { libc::getpagesize() as i64 }
+            } else {
+                unsafe { libc::sysconf(libc::_SC_PAGESIZE) as i64 }
+            }
+        }
+    }
+
     struct Limits(libc::rlimit);
     impl<'a> TryFromBorrowedObject<'a> for Limits {
         fn try_from_borrowed_object(vm: &VirtualMachine, obj: &'a PyObject) -> PyResult<Self> {
-            let seq: Vec<libc::rlim_t> = obj.try_to_value(vm)?;
-            match *seq {
-                [cur, max] => Ok(Self(libc::rlimit {
-                    rlim_cur: cur & RLIM_INFINITY,
-                    rlim_max: max & RLIM_INFINITY,
-                })),
-                _ => Err(vm.new_value_error("expected a tuple of 2 integers")),
-            }
+            let seq: Vec<BigInt> = obj.try_to_value(vm)?;
+            let [cur, max] = match seq.len() {
+                2 => [seq[0].clone(), seq[1].clone()],
+                _ => return Err(vm.new_value_error("expected a tuple of 2 integers")),
+            };
+            // `RLIM_INFINITY` and `-1` both mean "no limit" (CPython accepts
+            // either); anything else has to actually fit `rlim_t`, rather
+            // than getting silently masked down to one that does.
+            let to_rlim = |value: BigInt| -> PyResult<libc::rlim_t> {
+                if value == BigInt::from(-1) || value == BigInt::from(RLIM_INFINITY) {
+                    return Ok(RLIM_INFINITY);
+                }
+                value
+                    .to_u64()
+                    .ok_or_else(|| vm.new_overflow_error("int too large to convert".to_owned()))
+            };
+            Ok(Self(libc::rlimit {
+                rlim_cur: to_rlim(cur)?,
+                rlim_max: to_rlim(max)?,
+            }))
         }
     }
     impl ToPyObject for Limits {
@@ -189,4 +230,41 @@ mod resource {
             _ => e.to_pyexception(vm),
         })
     }
+
+    /// Query (`new_limit` absent) or atomically set-and-return-the-previous
+    /// (`new_limit` present) `resource`'s limits on an arbitrary process,
+    /// unlike `getrlimit`/`setrlimit` which only ever touch the caller.
+    #[cfg(target_os = "linux")]
+    #[pyfunction]
+    fn prlimit(
+        pid: libc::pid_t,
+        resource: i32,
+        new_limit: crate::vm::function::OptionalArg<Limits>,
+        vm: &VirtualMachine,
+    ) -> PyResult<Limits> {
+        #[allow(clippy::unnecessary_cast)]
+        if resource < 0 || resource >= RLIM_NLIMITS as i32 {
+            return Err(vm.new_value_error("invalid resource specified"));
+        }
+        let new_limit = new_limit.into_option();
+        let old_limit = unsafe {
+            let mut old = mem::MaybeUninit::<libc::rlimit>::uninit();
+            let new_ptr = new_limit
+                .as_ref()
+                .map_or(std::ptr::null(), |limits| &limits.0 as *const libc::rlimit);
+            if libc::prlimit(pid, resource as _, new_ptr, old.as_mut_ptr()) == -1 {
+                let e = io::Error::last_os_error();
+                return Err(match e.raw_os_error() {
+                    Some(libc::ESRCH) => vm.new_errno_error(libc::ESRCH, "no such process".to_owned()),
+                    Some(libc::EPERM) => {
+                        vm.new_errno_error(libc::EPERM, "not allowed to raise maximum limit".to_owned())
+                    }
+                    Some(libc::EINVAL) => vm.new_value_error("invalid resource specified"),
+                    _ => e.to_pyexception(vm),
+                });
+            }
+            old.assume_init()
+        };
+        Ok(Limits(old_limit))
+    }
 }
@@ -227,6 +227,198 @@ mod _io {
         vm.new_value_error("I/O operation on closed file")
     }
 
+    /// Copy `src` into `dst` reusing a single transfer buffer, the userspace path
+    /// taken by `copyfileobj` when the kernel fast paths do not apply. Both objects
+    /// are expected to expose the buffered `readinto`/`write` protocol; draining the
+    /// source with `readinto` avoids allocating a fresh bytes object per chunk.
+    pub(super) fn copy_buffered(
+        src: &PyObject,
+        dst: &PyObject,
+        length: usize,
+        vm: &VirtualMachine,
+    ) -> PyResult<u64> {
+        let buf = PyByteArray::from(vec![0u8; length.max(1)]).into_ref(&vm.ctx);
+        let mut total = 0;
+        loop {
+            let n = vm.call_method(src, "readinto", (buf.clone(),))?;
+            let n = <Option<usize>>::try_from_object(vm, n)?.unwrap_or(0);
+            if n == 0 {
+                break;
+            }
+            let chunk = buf.borrow_buf()[..n].to_vec();
+            vm.call_method(dst, "write", (vm.ctx.new_bytes(chunk),))?;
+            total += n as u64;
+        }
+        Ok(total)
+    }
+
+    /// Copy up to `count` bytes from `src` to `dst` entirely in the kernel using
+    /// `sendfile`, returning the number of bytes transferred. Both descriptors must
+    /// refer to real files (no pipes); callers fall back to a userspace copy when
+    /// this returns `ENOSYS`/`EINVAL`. This backs the `copyfileobj` fast path.
+    #[cfg(target_os = "linux")]
+    pub(super) fn sendfile_all(
+        src: &std::fs::File,
+        dst: &std::fs::File,
+        mut count: u64,
+    ) -> io::Result<u64> {
+        use std::os::fd::AsRawFd;
+        let (src_fd, dst_fd) = (src.as_raw_fd(), dst.as_raw_fd());
+        let mut total = 0;
+        while count > 0 {
+            let want = count.min(0x7fff_f000) as usize;
+            let n = unsafe {
+                libc::sendfile(dst_fd, src_fd, std::ptr::null_mut(), want)
+            };
+            if n < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if n == 0 {
+                break;
+            }
+            total += n as u64;
+            count -= n as u64;
+        }
+        Ok(total)
+    }
+
+    /// Copy the remainder of `src` (from its current position to EOF) into `dst`
+    /// starting at `dst`'s current position, while preserving sparseness: runs of
+    /// holes in the source are located with `SEEK_DATA`/`SEEK_HOLE` and reproduced
+    /// as holes in the destination, so only the data extents are actually read and
+    /// written. Both files are advanced to just past what was copied. Returns the
+    /// number of bytes spanned (including holes), i.e. how far `src` advanced.
+    ///
+    /// Positions are always relative to where each file already was -- this never
+    /// assumes `src` starts at 0 or that `dst` is empty, since either can be an
+    /// arbitrary stream midway through other I/O.
+    ///
+    /// This is the primitive a sparse-aware `copyfileobj` fast path builds on; on
+    /// platforms without hole punching the caller should fall back to a plain copy.
+    #[cfg(any(target_os = "dragonfly", target_os = "freebsd", target_os = "linux"))]
+    pub(super) fn sparse_copy(src: &std::fs::File, dst: &std::fs::File) -> io::Result<u64> {
+        use std::io::{Read, Seek, Write};
+        use std::os::fd::AsRawFd;
+
+        let (src_fd, dst_fd) = (src.as_raw_fd(), dst.as_raw_fd());
+        let end = src.metadata()?.len();
+        let mut src = src;
+        let mut dst = dst;
+        let src_start = src.stream_position()?;
+        let dst_start = dst.stream_position()?;
+        let mut pos = src_start;
+        let mut buf = vec![0u8; DEFAULT_BUFFER_SIZE];
+        while pos < end {
+            // Skip to the next region that actually holds data.
+            let data = unsafe { libc::lseek(src_fd, pos as libc::off_t, libc::SEEK_DATA) };
+            if data < 0 {
+                let err = io::Error::last_os_error();
+                if err.raw_os_error() == Some(libc::ENXIO) {
+                    // The rest of the file is a single trailing hole.
+                    break;
+                }
+                // Anything else (e.g. `EINVAL`, the filesystem doesn't
+                // support `SEEK_DATA`/`SEEK_HOLE`) is a real failure; the
+                // caller falls back to a plain copy rather than reporting a
+                // bogus byte count for data we never actually copied.
+                return Err(err);
+            }
+            let data = data as u64;
+            let hole = unsafe { libc::lseek(src_fd, data as libc::off_t, libc::SEEK_HOLE) };
+            if hole < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let hole = (hole as u64).min(end);
+            // Translate the source's absolute extent into one relative to where
+            // `dst` started, rather than replaying `src`'s own absolute offsets
+            // onto `dst` (which is wrong whenever the two don't already match).
+            let dst_at = dst_start + (data - src_start);
+            src.seek(SeekFrom::Start(data))?;
+            dst.seek(SeekFrom::Start(dst_at))?;
+            let mut remaining = hole - data;
+            while remaining > 0 {
+                let want = remaining.min(buf.len() as u64) as usize;
+                let n = src.read(&mut buf[..want])?;
+                if n == 0 {
+                    break;
+                }
+                dst.write_all(&buf[..n])?;
+                remaining -= n as u64;
+            }
+            pos = hole;
+        }
+        let copied = end - src_start;
+        let dst_end = dst_start + copied;
+        // If `src` ends in a trailing hole we never wrote, make sure `dst`
+        // reaches far enough to reproduce it -- but never shrink `dst` if it
+        // was already longer than that; it may have its own trailing content
+        // we have no business touching.
+        if dst.metadata()?.len() < dst_end {
+            unsafe { libc::ftruncate(dst_fd, dst_end as libc::off_t) };
+        }
+        src.seek(SeekFrom::Start(end))?;
+        dst.seek(SeekFrom::Start(dst_end))?;
+        Ok(copied)
+    }
+
+    /// Copy the remaining contents of `src` into `dst`, the accelerator
+    /// behind `shutil.copyfileobj`. When both objects are backed by a real
+    /// file descriptor this prefers a sparse-aware copy, then a pure
+    /// kernel-space `sendfile`, falling back to a portable buffered copy
+    /// (via `readinto`/`write`) whenever a fast path isn't available or
+    /// doesn't apply (pipes, sockets, non-Linux/BSD targets, ...).
+    #[pyfunction]
+    fn copyfileobj(
+        src: PyObjectRef,
+        dst: PyObjectRef,
+        length: OptionalSize,
+        vm: &VirtualMachine,
+    ) -> PyResult<u64> {
+        let length = length.to_usize().unwrap_or(DEFAULT_BUFFER_SIZE);
+
+        #[cfg(any(target_os = "dragonfly", target_os = "freebsd", target_os = "linux"))]
+        if let (Ok(src_fd), Ok(dst_fd)) = (
+            Fildes::try_from_object(vm, src.clone()),
+            Fildes::try_from_object(vm, dst.clone()),
+        ) {
+            use std::{fs::File, mem::ManuallyDrop, os::fd::FromRawFd};
+            // The Python file objects still own these descriptors and will
+            // close them themselves; wrap in `ManuallyDrop` so the temporary
+            // `File`s we construct here don't also close them on drop.
+            let src_file = ManuallyDrop::new(unsafe { File::from_raw_fd(src_fd.0) });
+            let dst_file = ManuallyDrop::new(unsafe { File::from_raw_fd(dst_fd.0) });
+
+            // `src`/`dst` are the *shared* fds backing the Python file objects,
+            // not private dups, and `sparse_copy` seeks them around to hop
+            // between data/hole extents. Snapshot both positions first: if it
+            // fails partway, the fds are left wherever it gave up, and falling
+            // through to another fast path (or the buffered fallback) without
+            // rewinding them first would silently read or write from the wrong
+            // place instead of resuming where the caller actually was.
+            let start_positions = unsafe {
+                let s = libc::lseek(src_fd.0, 0, libc::SEEK_CUR);
+                let d = libc::lseek(dst_fd.0, 0, libc::SEEK_CUR);
+                (s >= 0 && d >= 0).then_some((s, d))
+            };
+            if let Some((src_pos, dst_pos)) = start_positions {
+                match sparse_copy(&src_file, &dst_file) {
+                    Ok(n) => return Ok(n),
+                    Err(_) => unsafe {
+                        libc::lseek(src_fd.0, src_pos, libc::SEEK_SET);
+                        libc::lseek(dst_fd.0, dst_pos, libc::SEEK_SET);
+                    },
+                }
+
+                #[cfg(target_os = "linux")]
+                if let Ok(n) = sendfile_all(&src_file, &dst_file, u64::MAX) {
+                    return Ok(n);
+                }
+            }
+        }
+
+        copy_buffered(&src, &dst, length, vm)
+    }
+
     #[pyattr]
     const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
 
@@ -289,6 +481,17 @@ mod _io {
             Some(b)
         }
 
+        // Return up to `bytes` bytes from the current position without advancing it.
+        fn peek(&self, bytes: Option<usize>) -> Option<Vec<u8>> {
+            let pos = self.cursor.position().to_usize()?;
+            let avail_slice = self.cursor.get_ref().get(pos..)?;
+            let n = bytes.map_or_else(
+                || avail_slice.len(),
+                |n| std::cmp::min(n, avail_slice.len()),
+            );
+            Some(avail_slice[..n].to_vec())
+        }
+
         const fn tell(&self) -> u64 {
             self.cursor.position()
         }
@@ -757,6 +960,7 @@ mod _io {
             const DETACHED = 1 << 0;
             const WRITABLE = 1 << 1;
             const READABLE = 1 << 2;
+            const LINE_BUFFERED = 1 << 3;
         }
     }
 
@@ -771,6 +975,11 @@ mod _io {
         read_end: Offset,
         write_pos: Offset,
         write_end: Offset,
+        /// Persistent scratch buffer for reads that bypass the main buffer. Its
+        /// allocation is kept between calls and only the freshly grown tail is
+        /// zeroed, so a stream of `read1()` calls neither reallocates nor re-zeros
+        /// the bytes that a previous read already touched.
+        scratch: Vec<u8>,
     }
 
     impl BufferedData {
@@ -797,6 +1006,11 @@ mod _io {
             self.flags.contains(BufferedFlags::READABLE)
         }
 
+        #[inline]
+        const fn line_buffered(&self) -> bool {
+            self.flags.contains(BufferedFlags::LINE_BUFFERED)
+        }
+
         #[inline]
         const fn valid_read(&self) -> bool {
             self.readable() && self.read_end != -1
@@ -988,6 +1202,50 @@ mod _io {
             Ok(Some(n as usize))
         }
 
+        /// Keep calling the raw stream's `writev()` until every byte across
+        /// `bufs` has gone out, trimming fully-sent buffers and the leading
+        /// edge of a partially-sent one between calls the way a short
+        /// `write()` would be retried. Returns the total bytes written.
+        fn raw_writev_all(
+            &mut self,
+            raw: &PyObject,
+            mut bufs: Vec<Vec<u8>>,
+            vm: &VirtualMachine,
+        ) -> PyResult<usize> {
+            let total: usize = bufs.iter().map(Vec::len).sum();
+            let mut written = 0;
+            while written < total {
+                bufs.retain(|b| !b.is_empty());
+                let objs: Vec<PyObjectRef> =
+                    bufs.iter().map(|b| vm.ctx.new_bytes(b.clone()).into()).collect();
+                let n = usize::try_from_object(
+                    vm,
+                    vm.call_method(raw, "writev", (vm.ctx.new_list(objs),))?,
+                )?;
+                if n == 0 {
+                    break;
+                }
+                written += n;
+                let mut rem = n;
+                for b in bufs.iter_mut() {
+                    if rem == 0 {
+                        break;
+                    }
+                    if rem >= b.len() {
+                        rem -= b.len();
+                        b.clear();
+                    } else {
+                        b.drain(..rem);
+                        rem = 0;
+                    }
+                }
+            }
+            if self.abs_pos != -1 {
+                self.abs_pos += written as Offset;
+            }
+            Ok(written)
+        }
+
         fn write(&mut self, obj: ArgBytesLike, vm: &VirtualMachine) -> PyResult<usize> {
             if !self.valid_read() && !self.valid_write() {
                 self.pos = 0;
@@ -995,9 +1253,21 @@ mod _io {
             }
             let avail = self.buffer.len() - self.pos as usize;
             let buf_len;
+            // In line-buffered mode the pending bytes are flushed as soon as the
+            // incoming data carries a newline, so interactive streams see each line
+            // land on the raw layer without an explicit flush().
+            let has_newline = self.line_buffered() && obj.borrow_buf().contains(&b'\n');
             {
                 let buf = obj.borrow_buf();
                 buf_len = buf.len();
+                // Large-write bypass: if this single write is at least a whole
+                // buffer long and does not fit in the space left, there is nothing
+                // to be gained from copying it in. Flush the pending bytes and hand the
+                // payload straight to the raw layer below.
+                if buf_len >= self.buffer.len() && buf_len > avail {
+                    drop(buf);
+                    return self.write_bypass(obj, buf_len, vm);
+                }
                 if buf.len() <= avail {
                     self.buffer[self.pos as usize..][..buf.len()].copy_from_slice(&buf);
                     if !self.valid_write() || self.write_pos > self.pos {
@@ -1007,7 +1277,66 @@ mod _io {
                     if self.pos > self.write_end {
                         self.write_end = self.pos
                     }
-                    return Ok(buf.len());
+                    let written = buf.len();
+                    drop(buf);
+                    if has_newline {
+                        self.flush(vm)?;
+                    }
+                    return Ok(written);
+                }
+            }
+
+            let written = self.write_bypass(obj, buf_len, vm)?;
+            if has_newline {
+                self.flush(vm)?;
+            }
+            Ok(written)
+        }
+
+        /// Flush whatever is pending and write `obj` straight through to the raw
+        /// layer, buffering only the trailing remainder that is smaller than the
+        /// buffer. This is the path taken when a write is too large to be absorbed
+        /// by the current buffer.
+        ///
+        /// When the raw stream exposes `writev()`, the pending bytes and `obj` are
+        /// combined into a single scatter/gather call instead of a `flush()`
+        /// followed by a separate `write()`. Streams without `writev()` fall back
+        /// to the plain sequential flush-then-write below.
+        fn write_bypass(
+            &mut self,
+            obj: ArgBytesLike,
+            buf_len: usize,
+            vm: &VirtualMachine,
+        ) -> PyResult<usize> {
+            if self.valid_write() && self.write_pos < self.write_end {
+                if let Some(raw) = self.raw.clone() {
+                    if vm.get_attribute_opt(raw.clone(), "writev")?.is_some() {
+                        let pending =
+                            self.buffer[self.write_pos as usize..self.write_end as usize].to_vec();
+                        let rewind = self.raw_offset() + (self.pos - self.write_pos);
+                        if rewind != 0 {
+                            self.raw_seek(-rewind, 1, vm)?;
+                            self.raw_pos = -rewind;
+                        }
+                        let payload = obj.borrow_buf().to_vec();
+                        let want = pending.len() + payload.len();
+                        let written = self.raw_writev_all(&raw, vec![pending, payload], vm)?;
+                        self.reset_write();
+                        if self.readable() {
+                            self.reset_read();
+                        }
+                        self.write_pos = 0;
+                        self.write_end = -1;
+                        self.raw_pos = 0;
+                        self.adjust_position(0);
+                        if written < want {
+                            return Err(vm.new_exception_msg(
+                                vm.ctx.exceptions.blocking_io_error.to_owned(),
+                                "write could not complete without blocking".to_owned(),
+                            ));
+                        }
+                        return Ok(buf_len);
+                    }
                 }
             }
 
@@ -1074,6 +1403,74 @@ mod _io {
             Ok(written)
         }
 
+        /// Write a batch of buffers. When the batch is too big to be absorbed by
+        /// the buffer and the raw stream has `writev()`, the pending bytes and
+        /// every line go to `writev()` as separate buffers in one gather call,
+        /// without first joining the lines into one payload. Streams without
+        /// `writev()` have no way to take scattered buffers, so they fall back to
+        /// gathering the lines into one payload before sending it through
+        /// `write_bypass`.
+        fn write_many(&mut self, lines: Vec<ArgBytesLike>, vm: &VirtualMachine) -> PyResult<()> {
+            let total: usize = lines.iter().map(|l| l.borrow_buf().len()).sum();
+            if total == 0 {
+                return Ok(());
+            }
+            let avail = self.buffer.len() - self.pos as usize;
+            if total > avail && total >= self.buffer.len() {
+                let raw = self.raw.clone();
+                let vectored = match &raw {
+                    Some(raw) => vm.get_attribute_opt(raw.clone(), "writev")?.is_some(),
+                    None => false,
+                };
+                if vectored {
+                    let raw = raw.unwrap();
+                    let pending = self.valid_write() && self.write_pos < self.write_end;
+                    let rewind = if pending {
+                        self.raw_offset() + (self.pos - self.write_pos)
+                    } else {
+                        self.raw_offset()
+                    };
+                    if rewind != 0 {
+                        self.raw_seek(-rewind, 1, vm)?;
+                        self.raw_pos = -rewind;
+                    }
+                    let mut bufs: Vec<Vec<u8>> = Vec::with_capacity(lines.len() + 1);
+                    if pending {
+                        bufs.push(self.buffer[self.write_pos as usize..self.write_end as usize].to_vec());
+                    }
+                    let want = total + bufs.first().map_or(0, Vec::len);
+                    bufs.extend(lines.iter().map(|l| l.borrow_buf().to_vec()));
+                    let written = self.raw_writev_all(&raw, bufs, vm)?;
+                    self.reset_write();
+                    if self.readable() {
+                        self.reset_read();
+                    }
+                    self.write_pos = 0;
+                    self.write_end = -1;
+                    self.raw_pos = 0;
+                    self.adjust_position(0);
+                    if written < want {
+                        return Err(vm.new_exception_msg(
+                            vm.ctx.exceptions.blocking_io_error.to_owned(),
+                            "write could not complete without blocking".to_owned(),
+                        ));
+                    }
+                    return Ok(());
+                }
+                let mut gathered = Vec::with_capacity(total);
+                for line in &lines {
+                    gathered.extend_from_slice(&line.borrow_buf());
+                }
+                let obj = ArgBytesLike::try_from_object(vm, vm.ctx.new_bytes(gathered).into())?;
+                self.write_bypass(obj, total, vm)?;
+            } else {
+                for line in lines {
+                    self.write(line, vm)?;
+                }
+            }
+            Ok(())
+        }
+
         fn active_read_slice(&self) -> &[u8] {
             &self.buffer[self.pos as usize..][..self.readahead() as usize]
         }
@@ -1335,7 +1732,10 @@ mod _io {
 
             let mut remaining = buf_len - written;
             while remaining > 0 {
-                let n = if remaining > self.buffer.len() {
+                // Bypass the internal buffer entirely when the caller wants at least
+                // a full buffer's worth: read straight into the destination and skip
+                // the fill-then-copy round trip.
+                let n = if remaining >= self.buffer.len() {
                     self.raw_read(Either::B(buf.clone()), written..written + remaining, vm)?
                 } else if !(readinto1 && written != 0) {
                     let n = self.fill_buffer(vm)?;
@@ -1553,6 +1953,20 @@ mod _io {
             Ok(self.lock(vm)?.raw.clone())
         }
 
+        #[pygetset]
+        fn line_buffering(&self, vm: &VirtualMachine) -> PyResult<bool> {
+            Ok(self.lock(vm)?.line_buffered())
+        }
+
+        #[pygetset(setter)]
+        fn set_line_buffering(&self, value: bool, vm: &VirtualMachine) -> PyResult<()> {
+            let mut data = self.lock(vm)?;
+            data.flags.set(BufferedFlags::LINE_BUFFERED, value);
+            // Flushing through the last newline now would surprise a caller that
+            // just toggled the mode, so only subsequent writes are affected.
+            Ok(())
+        }
+
         #[pygetset]
         fn closed(&self, vm: &VirtualMachine) -> PyResult {
             self.lock(vm)?.check_init(vm)?.get_attr("closed", vm)
@@ -1689,14 +2103,19 @@ mod _io {
                 let n = std::cmp::min(have as usize, n);
                 return Ok(data.read_fast(n).unwrap());
             }
-            let mut v = vec![0; n];
+            let mut v = std::mem::take(&mut data.scratch);
+            if v.len() < n {
+                // Only the grown tail needs initializing; the reused prefix will be
+                // overwritten by readinto() below.
+                v.resize(n, 0);
+            }
             data.reset_read();
             let r = data
                 .raw_read(Either::A(Some(&mut v)), 0..n, vm)?
                 .unwrap_or(0);
-            v.truncate(r);
-            v.shrink_to_fit();
-            Ok(v)
+            let out = v[..r].to_vec();
+            data.scratch = v;
+            Ok(out)
         }
 
         #[pymethod]
@@ -1782,6 +2201,18 @@ mod _io {
             ensure_unclosed(raw, "flush of closed file", vm)?;
             data.flush_rewind(vm)
         }
+
+        #[pymethod]
+        fn writelines(&self, lines: ArgIterable, vm: &VirtualMachine) -> PyResult<()> {
+            let lines = lines
+                .iter(vm)?
+                .map(|line| ArgBytesLike::try_from_object(vm, line?))
+                .collect::<PyResult<Vec<_>>>()?;
+            let mut data = self.writer().lock(vm)?;
+            let raw = data.check_init(vm)?;
+            ensure_unclosed(raw, "write to closed file", vm)?;
+            data.write_many(lines, vm)
+        }
     }
 
     #[pyattr]
@@ -1918,6 +2349,13 @@ mod _io {
             true
         }
 
+        #[pymethod]
+        const fn seekable(&self) -> bool {
+            // The two halves advance independently, so there is no single position
+            // to seek to.
+            false
+        }
+
         #[pygetset]
         fn closed(&self, vm: &VirtualMachine) -> PyResult {
             self.write.closed(vm)
@@ -2113,9 +2551,17 @@ mod _io {
         newline: Newlines,
         line_buffering: bool,
         write_through: bool,
+        /// Opt-in Nagle-style coalescing: when set, small writes keep accumulating in
+        /// the pending queue past `chunk_size` (up to a higher water mark) so that a
+        /// burst of tiny writes collapses into fewer calls to the underlying buffer.
+        write_coalesce: bool,
         chunk_size: usize,
         seekable: bool,
         has_read1: bool,
+        has_readinto: bool,
+        /// Reused `readinto` target so the text read path does not allocate a fresh
+        /// transfer buffer for every refill.
+        read_scratch: Option<PyRef<PyByteArray>>,
         // these are more state than configuration
         pending: PendingWrites,
         telling: bool,
@@ -2153,6 +2599,15 @@ mod _io {
                 Self::Bytes(b) => b.as_bytes(),
             }
         }
+
+        /// Hand a single segment to the underlying buffer without joining it with
+        /// its neighbours; an already-encoded `Bytes` chunk is forwarded as-is.
+        fn into_pyobject(self, vm: &VirtualMachine) -> PyObjectRef {
+            match self {
+                Self::Utf8(s) => vm.ctx.new_bytes(s.as_bytes().to_vec()).into(),
+                Self::Bytes(b) => b.into(),
+            }
+        }
     }
 
     impl PendingWrites {
@@ -2167,19 +2622,10 @@ mod _io {
                 }
             }
         }
-        fn take(&mut self, vm: &VirtualMachine) -> PyBytesRef {
-            let Self { num_bytes, data } = std::mem::take(self);
-            if let PendingWritesData::One(PendingWrite::Bytes(b)) = data {
-                return b;
-            }
-            let writes_iter = match data {
-                PendingWritesData::None => itertools::Either::Left(vec![].into_iter()),
-                PendingWritesData::One(write) => itertools::Either::Right(std::iter::once(write)),
-                PendingWritesData::Many(writes) => itertools::Either::Left(writes.into_iter()),
-            };
-            let mut buf = Vec::with_capacity(num_bytes);
-            writes_iter.for_each(|chunk| buf.extend_from_slice(chunk.as_bytes()));
-            PyBytes::from(buf).into_ref(&vm.ctx)
+        /// Detach the queued segments without concatenating them, so the caller can
+        /// emit each one to the underlying buffer in turn.
+        fn take_segments(&mut self) -> PendingWritesData {
+            std::mem::take(self).data
         }
     }
 
@@ -2310,6 +2756,8 @@ mod _io {
                 .unwrap_or_else(|| identifier!(vm, strict).to_owned());
 
             let has_read1 = vm.get_attribute_opt(buffer.clone(), "read1")?.is_some();
+            let readinto_name = if has_read1 { "readinto1" } else { "readinto" };
+            let has_readinto = vm.get_attribute_opt(buffer.clone(), readinto_name)?.is_some();
             let seekable = vm.call_method(&buffer, "seekable", ())?.try_to_bool(vm)?;
 
             let newline = args.newline.unwrap_or_default();
@@ -2325,9 +2773,12 @@ mod _io {
                 newline,
                 line_buffering: args.line_buffering.unwrap_or_default(),
                 write_through: args.write_through.unwrap_or_default(),
+                write_coalesce: false,
                 chunk_size: 8192,
                 seekable,
                 has_read1,
+                has_readinto,
+                read_scratch: None,
 
                 pending: PendingWrites::default(),
                 telling: seekable,
@@ -2409,32 +2860,53 @@ mod _io {
     impl TextIOWrapper {
         #[pymethod]
         fn reconfigure(&self, args: TextIOWrapperArgs, vm: &VirtualMachine) -> PyResult<()> {
-            let mut data = self.data.lock().unwrap();
-            if let Some(data) = data.as_mut() {
-                if let Some(encoding) = args.encoding {
-                    let (encoder, decoder) = Self::find_coder(
-                        &data.buffer,
-                        encoding.as_str(),
-                        &data.errors,
-                        data.newline,
-                        vm,
-                    )?;
-                    data.encoding = encoding;
-                    data.encoder = encoder;
-                    data.decoder = decoder;
-                }
-                if let Some(errors) = args.errors {
-                    data.errors = errors;
-                }
-                if let Some(newline) = args.newline {
-                    data.newline = newline;
-                }
-                if let Some(line_buffering) = args.line_buffering {
-                    data.line_buffering = line_buffering;
-                }
-                if let Some(write_through) = args.write_through {
-                    data.write_through = write_through;
+            let mut data = self.lock(vm)?;
+
+            // Changing the codec is only meaningful before any byte has been decoded,
+            // otherwise the already-read bytes would be reinterpreted inconsistently.
+            let coder_change =
+                args.encoding.is_some() || args.newline.is_some() || args.errors.is_some();
+            if coder_change && data.snapshot.is_some() {
+                return Err(new_unsupported_operation(
+                    vm,
+                    "It is not possible to set the encoding or newline of stream after the first read"
+                        .to_owned(),
+                ));
+            }
+
+            if let Some(errors) = args.errors {
+                data.errors = errors;
+            }
+            if let Some(newline) = args.newline {
+                data.newline = newline;
+            }
+
+            // Any pending output has to hit the buffer before the encoder is swapped
+            // out from under it.
+            data.write_pending(vm)?;
+            vm.call_method(&data.buffer, "flush", ())?;
+
+            if coder_change {
+                let encoding = args.encoding.unwrap_or_else(|| data.encoding.clone());
+                let (encoder, decoder) =
+                    Self::find_coder(&data.buffer, encoding.as_str(), &data.errors, data.newline, vm)?;
+                data.encoding = encoding;
+                data.encoder = encoder;
+                data.decoder = decoder;
+                if let Some((encoder, _)) = &data.encoder {
+                    reset_encoder(encoder, true)?;
                 }
+                // Drop any decode state left over from the previous codec.
+                data.snapshot = None;
+                data.decoded_chars = None;
+                data.decoded_chars_used = Utf8size::default();
+            }
+
+            if let Some(line_buffering) = args.line_buffering {
+                data.line_buffering = line_buffering;
+            }
+            if let Some(write_through) = args.write_through {
+                data.write_through = write_through;
             }
             Ok(())
         }
@@ -2467,6 +2939,17 @@ mod _io {
             Ok(self.lock(vm)?.write_through)
         }
 
+        #[pygetset(name = "_write_coalesce")]
+        fn write_coalesce(&self, vm: &VirtualMachine) -> PyResult<bool> {
+            Ok(self.lock(vm)?.write_coalesce)
+        }
+
+        #[pygetset(setter, name = "_write_coalesce")]
+        fn set_write_coalesce(&self, value: bool, vm: &VirtualMachine) -> PyResult<()> {
+            self.lock(vm)?.write_coalesce = value;
+            Ok(())
+        }
+
         #[pygetset]
         fn newlines(&self, vm: &VirtualMachine) -> PyResult<Option<PyObjectRef>> {
             let data = self.lock(vm)?;
@@ -2766,6 +3249,10 @@ mod _io {
             textio.write_pending(vm)?;
 
             let s = if let Some(mut remaining) = size.to_usize() {
+                // Collect each decoded slice as it comes out of the decoder and join
+                // them in one pass at the end. Concatenating pairwise on every refill
+                // would recopy the accumulated prefix each time and turn a sized read
+                // spanning k chunks into O(k·n) work.
                 let mut chunks = Vec::new();
                 let mut chunks_bytes = 0;
                 loop {
@@ -2782,16 +3269,16 @@ mod _io {
                         break;
                     }
                 }
-                if chunks.is_empty() {
-                    vm.ctx.empty_str.to_owned()
-                } else if chunks.len() == 1 {
-                    chunks.pop().unwrap()
-                } else {
-                    let mut ret = Wtf8Buf::with_capacity(chunks_bytes);
-                    for chunk in chunks {
-                        ret.push_wtf8(chunk.as_wtf8())
+                match chunks.len() {
+                    0 => vm.ctx.empty_str.to_owned(),
+                    1 => chunks.pop().unwrap(),
+                    _ => {
+                        let mut ret = Wtf8Buf::with_capacity(chunks_bytes);
+                        for chunk in chunks {
+                            ret.push_wtf8(chunk.as_wtf8())
+                        }
+                        PyStr::from(ret).into_ref(&vm.ctx)
                     }
-                    PyStr::from(ret).into_ref(&vm.ctx)
                 }
             } else {
                 let bytes = vm.call_method(&textio.buffer, "read", ())?;
@@ -2828,6 +3315,31 @@ mod _io {
             let has_lf = (replace_nl.is_some() || textio.line_buffering)
                 && data.contains_code_point('\n'.into());
             let flush = textio.line_buffering && (has_lf || data.contains_code_point('\r'.into()));
+
+            // Line-buffered fast path (LineWriterShim): flush only through the final
+            // newline and keep the trailing partial line queued, instead of forcing
+            // the whole buffer out. '\n' is never a continuation byte, so splitting
+            // on its last occurrence always lands on a code point boundary.
+            if flush && !textio.write_through && has_lf {
+                let translated = if let Some(replace_nl) = replace_nl {
+                    Cow::Owned(data.replace("\n".as_ref(), replace_nl.as_ref()))
+                } else {
+                    Cow::Borrowed(data)
+                };
+                if let Some(idx) = translated.as_bytes().iter().rposition(|&b| b == b'\n') {
+                    let head = PyStr::from(translated[..idx + 1].to_owned()).into_ref(&vm.ctx);
+                    textio.encode_and_push(head, vm)?;
+                    textio.write_pending(vm)?;
+                    let _ = vm.call_method(&textio.buffer, "flush", ());
+                    let tail = &translated[idx + 1..];
+                    if !tail.is_empty() {
+                        let tail = PyStr::from(tail.to_owned()).into_ref(&vm.ctx);
+                        textio.encode_and_push(tail, vm)?;
+                    }
+                    return Ok(char_len);
+                }
+            }
+
             let chunk = if let Some(replace_nl) = replace_nl {
                 if has_lf {
                     PyStr::from(data.replace("\n".as_ref(), replace_nl.as_ref())).into_ref(&vm.ctx)
@@ -2856,11 +3368,12 @@ mod _io {
                         }
                     })?
             };
-            if textio.pending.num_bytes + chunk.as_bytes().len() > textio.chunk_size {
+            let high_water = textio.write_high_water();
+            if textio.pending.num_bytes + chunk.as_bytes().len() > high_water {
                 textio.write_pending(vm)?;
             }
             textio.pending.push(chunk);
-            if flush || textio.write_through || textio.pending.num_bytes >= textio.chunk_size {
+            if flush || textio.write_through || textio.pending.num_bytes >= high_water {
                 textio.write_pending(vm)?;
             }
             if flush {
@@ -3104,12 +3617,84 @@ mod _io {
     }
 
     impl TextIOData {
+        /// The number of queued bytes at which the pending writes are pushed down to
+        /// the underlying buffer. Nagle-style coalescing raises this above the read
+        /// `chunk_size` so tiny writes keep merging.
+        const fn write_high_water(&self) -> usize {
+            if self.write_coalesce {
+                self.chunk_size.saturating_mul(8)
+            } else {
+                self.chunk_size
+            }
+        }
+
+        /// Encode `chunk` through the incremental encoder and queue it, flushing the
+        /// pending buffer first if the chunk would overflow the water mark and again
+        /// afterwards once the queue is full. The line-buffered write path queues
+        /// through this; the plain path in `write` performs the same steps inline.
+        fn encode_and_push(&mut self, chunk: PyStrRef, vm: &VirtualMachine) -> PyResult<()> {
+            let (encoder, encode_func) = self
+                .encoder
+                .as_ref()
+                .ok_or_else(|| new_unsupported_operation(vm, "not writable".to_owned()))?;
+            let chunk = if let Some(encode_func) = *encode_func {
+                encode_func(chunk)
+            } else {
+                let b = vm.call_method(encoder, "encode", (chunk.clone(),))?;
+                b.downcast::<PyBytes>()
+                    .map(PendingWrite::Bytes)
+                    .or_else(|obj| {
+                        if obj.is(&chunk) {
+                            Ok(PendingWrite::Utf8(chunk))
+                        } else {
+                            Err(vm.new_type_error(format!(
+                                "encoder should return a bytes object, not '{}'",
+                                obj.class().name()
+                            )))
+                        }
+                    })?
+            };
+            let high_water = self.write_high_water();
+            if self.pending.num_bytes + chunk.as_bytes().len() > high_water {
+                self.write_pending(vm)?;
+            }
+            self.pending.push(chunk);
+            if self.pending.num_bytes >= high_water {
+                self.write_pending(vm)?;
+            }
+            Ok(())
+        }
+
         fn write_pending(&mut self, vm: &VirtualMachine) -> PyResult<()> {
             if self.pending.num_bytes == 0 {
                 return Ok(());
             }
-            let data = self.pending.take(vm);
-            vm.call_method(&self.buffer, "write", (data,))?;
+            // Emit each queued segment separately instead of joining them into one
+            // contiguous allocation first; the buffered writer underneath already
+            // coalesces the segments back up to its buffer size.
+            match self.pending.take_segments() {
+                PendingWritesData::None => {}
+                PendingWritesData::One(write) => {
+                    let obj = write.into_pyobject(vm);
+                    vm.call_method(&self.buffer, "write", (obj,))?;
+                }
+                PendingWritesData::Many(writes) => {
+                    // Hand the whole batch to the buffer in one gather call when it
+                    // supports writelines(); this lets a vectored writer emit all the
+                    // segments without our ever joining them into one allocation. Fall
+                    // back to writing each segment when writelines() is unavailable.
+                    let segments: Vec<PyObjectRef> =
+                        writes.into_iter().map(|w| w.into_pyobject(vm)).collect();
+                    if vm.get_attribute_opt(self.buffer.clone(), "writelines")?.is_some() {
+                        let list = vm.ctx.new_list(segments);
+                        vm.call_method(&self.buffer, "writelines", (list,))?;
+                    } else {
+                        for obj in segments {
+                            vm.call_method(&self.buffer, "write", (obj,))?;
+                        }
+                    }
+                }
+            }
             Ok(())
         }
 
@@ -3117,35 +3702,49 @@ mod _io {
         fn read_chunk(&mut self, size_hint: usize, vm: &VirtualMachine) -> PyResult<bool> {
             let decoder = self
                 .decoder
-                .as_ref()
+                .clone()
                 .ok_or_else(|| new_unsupported_operation(vm, "not readable".to_owned()))?;
 
             let dec_state = if self.telling {
-                let state = vm.call_method(decoder, "getstate", ())?;
+                let state = vm.call_method(&decoder, "getstate", ())?;
                 Some(parse_decoder_state(state, vm)?)
             } else {
                 None
             };
 
-            let method = if self.has_read1 { "read1" } else { "read" };
             let size_hint = if size_hint > 0 {
                 (self.b2cratio.max(1.0) * size_hint as f64) as usize
             } else {
                 size_hint
             };
             let chunk_size = std::cmp::max(self.chunk_size, size_hint);
-            let input_chunk = vm.call_method(&self.buffer, method, (chunk_size,))?;
+            let input_chunk = if self.has_readinto {
+                // Fast path: read straight into a reused bytearray instead of letting
+                // the buffer allocate a fresh bytes object for every refill.
+                let readinto_name = if self.has_read1 { "readinto1" } else { "readinto" };
+                let scratch = match self.read_scratch.take() {
+                    Some(ba) if ba.borrow_buf().len() >= chunk_size => ba,
+                    _ => PyByteArray::from(vec![0u8; chunk_size]).into_ref(&vm.ctx),
+                };
+                let n = vm.call_method(&self.buffer, readinto_name, (scratch.clone(),))?;
+                let n = <Option<usize>>::try_from_object(vm, n)?.unwrap_or(0);
+                let bytes = vm.ctx.new_bytes(scratch.borrow_buf()[..n].to_vec());
+                self.read_scratch = Some(scratch);
+                bytes.into()
+            } else {
+                let method = if self.has_read1 { "read1" } else { "read" };
+                vm.call_method(&self.buffer, method, (chunk_size,))?
+            };
 
             let buf = ArgBytesLike::try_from_borrowed_object(vm, &input_chunk).map_err(|_| {
                 vm.new_type_error(format!(
-                    "underlying {}() should have returned a bytes-like object, not '{}'",
-                    method,
+                    "underlying read() should have returned a bytes-like object, not '{}'",
                     input_chunk.class().name()
                 ))
             })?;
             let nbytes = buf.borrow_buf().len();
             let eof = nbytes == 0;
-            let decoded = vm.call_method(decoder, "decode", (input_chunk, eof))?;
+            let decoded = vm.call_method(&decoder, "decode", (input_chunk, eof))?;
             let decoded = check_decoded(decoded, vm)?;
 
             let char_len = decoded.char_len();
@@ -3467,6 +4066,8 @@ mod _io {
     struct StringIO {
         buffer: PyRwLock<BufferedIO>,
         closed: AtomicCell<bool>,
+        newline: Newlines,
+        seennl: AtomicCell<SeenNewline>,
     }
 
     #[derive(FromArgs)]
@@ -3474,16 +4075,13 @@ mod _io {
         #[pyarg(positional, optional)]
         object: OptionalOption<PyStrRef>,
 
-        // TODO: use this
         #[pyarg(any, default)]
-        #[allow(dead_code)]
         newline: Newlines,
     }
 
     impl Constructor for StringIO {
         type Args = StringIONewArgs;
 
-        #[allow(unused_variables)]
         fn py_new(
             cls: PyTypeRef,
             Self::Args { object, newline }: Self::Args,
@@ -3496,6 +4094,8 @@ mod _io {
             Self {
                 buffer: PyRwLock::new(BufferedIO::new(Cursor::new(raw_bytes))),
                 closed: AtomicCell::new(false),
+                newline,
+                seennl: AtomicCell::new(SeenNewline::empty()),
             }
             .into_ref_with_type(vm, cls)
             .map(Into::into)
@@ -3510,6 +4110,72 @@ mod _io {
                 Err(io_closed_error(vm))
             }
         }
+
+        /// The sequence each `\n` is rewritten to on write, or `None` when writes
+        /// pass through untranslated.
+        const fn write_translation(&self) -> Option<&'static [u8]> {
+            match self.newline {
+                Newlines::Cr => Some(b"\r"),
+                Newlines::Crlf => Some(b"\r\n"),
+                Newlines::Universal | Newlines::Passthrough | Newlines::Lf => None,
+            }
+        }
+
+        /// Whether `\r`/`\r\n` terminators are tracked for `newlines` (and, for
+        /// `newline=None`, collapsed to `\n` in the returned text). `readline`
+        /// splits on `\n` only.
+        const fn read_universal(&self) -> bool {
+            matches!(self.newline, Newlines::Universal | Newlines::Passthrough)
+        }
+
+        /// Record which terminators appear in `text` so `newlines` can report them.
+        fn track_seen(&self, text: &[u8]) {
+            if !self.read_universal() {
+                return;
+            }
+            let mut seen = self.seennl.load();
+            let mut i = 0;
+            while i < text.len() {
+                match text[i] {
+                    b'\n' => {
+                        seen.insert(SeenNewline::LF);
+                        i += 1;
+                    }
+                    b'\r' => {
+                        if text.get(i + 1) == Some(&b'\n') {
+                            seen.insert(SeenNewline::CRLF);
+                            i += 2;
+                        } else {
+                            seen.insert(SeenNewline::CR);
+                            i += 1;
+                        }
+                    }
+                    _ => i += 1,
+                }
+            }
+            self.seennl.store(seen);
+        }
+
+        /// Collapse `\r\n` and lone `\r` to `\n` for universal-newline reads.
+        fn translate_read(&self, data: Vec<u8>) -> Vec<u8> {
+            if !matches!(self.newline, Newlines::Universal) || !data.contains(&b'\r') {
+                return data;
+            }
+            let mut out = Vec::with_capacity(data.len());
+            let mut i = 0;
+            while i < data.len() {
+                if data[i] == b'\r' {
+                    out.push(b'\n');
+                    if data.get(i + 1) == Some(&b'\n') {
+                        i += 1;
+                    }
+                } else {
+                    out.push(data[i]);
+                }
+                i += 1;
+            }
+            out
+        }
     }
 
     #[pyclass(flags(BASETYPE, HAS_DICT), with(Constructor))]
@@ -3543,9 +4209,30 @@ mod _io {
         #[pymethod]
         fn write(&self, data: PyStrRef, vm: &VirtualMachine) -> PyResult<u64> {
             let bytes = data.as_bytes();
+            self.track_seen(bytes);
+            // The return value counts the characters supplied by the caller, not the
+            // (possibly longer) translated bytes that reach the buffer.
+            let char_len = data.char_len() as u64;
+            let translated;
+            let to_write = match self.write_translation() {
+                Some(nl) if bytes.contains(&b'\n') => {
+                    let mut out = Vec::with_capacity(bytes.len());
+                    for &b in bytes {
+                        if b == b'\n' {
+                            out.extend_from_slice(nl);
+                        } else {
+                            out.push(b);
+                        }
+                    }
+                    translated = out;
+                    translated.as_slice()
+                }
+                _ => bytes,
+            };
             self.buffer(vm)?
-                .write(bytes)
-                .ok_or_else(|| vm.new_type_error("Error Writing String"))
+                .write(to_write)
+                .ok_or_else(|| vm.new_type_error("Error Writing String"))?;
+            Ok(char_len)
         }
 
         // return the entire contents of the underlying
@@ -3575,7 +4262,7 @@ mod _io {
         fn read(&self, size: OptionalSize, vm: &VirtualMachine) -> PyResult<Wtf8Buf> {
             let data = self.buffer(vm)?.read(size.to_usize()).unwrap_or_default();
 
-            let value = Wtf8Buf::from_bytes(data)
+            let value = Wtf8Buf::from_bytes(self.translate_read(data))
                 .map_err(|_| vm.new_value_error("Error Retrieving Value"))?;
             Ok(value)
         }
@@ -3590,7 +4277,22 @@ mod _io {
             // TODO size should correspond to the number of characters, at the moments its the number of
             // bytes.
             let input = self.buffer(vm)?.readline(size.to_usize(), vm)?;
-            Wtf8Buf::from_bytes(input).map_err(|_| vm.new_value_error("Error Retrieving Value"))
+            Wtf8Buf::from_bytes(self.translate_read(input))
+                .map_err(|_| vm.new_value_error("Error Retrieving Value"))
+        }
+
+        #[pygetset]
+        fn newlines(&self, vm: &VirtualMachine) -> PyObjectRef {
+            match self.seennl.load().bits() {
+                1 => "\n".to_pyobject(vm),
+                2 => "\r".to_pyobject(vm),
+                3 => ("\r", "\n").to_pyobject(vm),
+                4 => "\r\n".to_pyobject(vm),
+                5 => ("\n", "\r\n").to_pyobject(vm),
+                6 => ("\r", "\r\n").to_pyobject(vm),
+                7 => ("\r", "\n", "\r\n").to_pyobject(vm),
+                _ => vm.ctx.none(),
+            }
         }
 
         #[pymethod]
@@ -3718,6 +4420,13 @@ mod _io {
             self.buffer(vm)?.readline(size.to_usize(), vm)
         }
 
+        // Return bytes from the current position without advancing it, so callers can
+        // inspect upcoming bytes (magic numbers, delimiters) before consuming them.
+        #[pymethod]
+        fn peek(&self, size: OptionalSize, vm: &VirtualMachine) -> PyResult<Vec<u8>> {
+            Ok(self.buffer(vm)?.peek(size.to_usize()).unwrap_or_default())
+        }
+
         #[pymethod]
         fn truncate(&self, pos: OptionalSize, vm: &VirtualMachine) -> PyResult<usize> {
             if self.closed.load() {
@@ -4029,6 +4738,21 @@ mod _io {
         };
         let buffered = PyType::call(cls, (raw, buffering).into_args(vm), vm)?;
 
+        // A binary stream opened with buffering=1 (or a tty) flushes on newline at
+        // the buffered layer; for text streams the TextIOWrapper drives it instead.
+        if line_buffering && matches!(mode.encode, EncodeMode::Bytes) {
+            let data = if let Some(w) = buffered.downcast_ref::<BufferedWriter>() {
+                Some(w.data())
+            } else {
+                buffered.downcast_ref::<BufferedRandom>().map(|w| w.data())
+            };
+            if let Some(data) = data {
+                if let Some(mut data) = data.lock() {
+                    data.flags.insert(BufferedFlags::LINE_BUFFERED);
+                }
+            }
+        }
+
         match mode.encode {
             EncodeMode::Text => {
                 let tio = TextIOWrapper::static_type();
@@ -4250,8 +4974,12 @@ mod fileio {
         closefd: AtomicCell<bool>,
         mode: AtomicCell<Mode>,
         seekable: AtomicCell<Option<bool>>,
+        blksize: AtomicCell<i64>,
     }
 
+    // Optimal transfer size to assume when fstat reports something unusable.
+    const DEFAULT_BUFFER_SIZE: i64 = 8 * 1024;
+
     #[derive(FromArgs)]
     pub struct FileIOArgs {
         #[pyarg(positional)]
@@ -4262,6 +4990,10 @@ mod fileio {
         closefd: bool,
         #[pyarg(any, default)]
         opener: Option<PyObjectRef>,
+        // Extra system-specific open() flags (O_DIRECT, O_NONBLOCK, O_SYNC,
+        // O_NOFOLLOW, …) OR'd into the flags derived from the mode string.
+        #[pyarg(any, default)]
+        custom_flags: i32,
     }
 
     impl Default for FileIO {
@@ -4271,6 +5003,7 @@ mod fileio {
                 closefd: AtomicCell::new(true),
                 mode: AtomicCell::new(Mode::empty()),
                 seekable: AtomicCell::new(None),
+                blksize: AtomicCell::new(DEFAULT_BUFFER_SIZE),
             }
         }
     }
@@ -4297,8 +5030,11 @@ mod fileio {
                 .mode
                 .unwrap_or_else(|| PyUtf8Str::from("rb").into_ref(&vm.ctx));
             let mode_str = mode_obj.as_str();
-            let (mode, flags) =
+            let (mode, mut flags) =
                 compute_mode(mode_str).map_err(|e| vm.new_value_error(e.error_msg(mode_str)))?;
+            // Additively merge any caller-supplied system flags; the mode letters still
+            // decide the RDONLY/WRONLY/RDWR and create/exclusive bits.
+            flags |= args.custom_flags;
             zelf.mode.store(mode);
 
             let (fd, filename) = if let Some(fd) = arg_fd {
@@ -4356,6 +5092,11 @@ mod fileio {
                             let err = std::io::Error::from_raw_os_error(libc::EISDIR);
                             return Err(IOErrorBuilder::with_filename(&err, filename, vm));
                         }
+                        // Record the device's optimal transfer size; readall sizes its
+                        // chunks from it and `_blksize` reports it.
+                        if status.st_blksize > 1 {
+                            zelf.blksize.store(status.st_blksize as i64);
+                        }
                     }
                     Err(err) => {
                         if err.raw_os_error() == Some(libc::EBADF) {
@@ -4415,6 +5156,15 @@ mod fileio {
             exc
         }
 
+        // A non-blocking descriptor reports "would block" through EAGAIN/EWOULDBLOCK;
+        // RawIOBase maps that to None rather than an exception so callers can retry.
+        fn is_would_block(err: &std::io::Error) -> bool {
+            matches!(
+                err.raw_os_error(),
+                Some(e) if e == libc::EAGAIN || e == libc::EWOULDBLOCK
+            )
+        }
+
         #[pygetset]
         fn closed(&self) -> bool {
             self.fd.load() < 0
@@ -4480,7 +5230,7 @@ mod fileio {
             zelf: &Py<Self>,
             read_byte: OptionalSize,
             vm: &VirtualMachine,
-        ) -> PyResult<Vec<u8>> {
+        ) -> PyResult<Option<Vec<u8>>> {
             if !zelf.mode.load().contains(Mode::READABLE) {
                 return Err(new_unsupported_operation(
                     vm,
@@ -4490,24 +5240,57 @@ mod fileio {
             let mut handle = zelf.get_fd(vm)?;
             let bytes = if let Some(read_byte) = read_byte.to_usize() {
                 let mut bytes = vec![0; read_byte];
-                let n = handle
-                    .read(&mut bytes)
-                    .map_err(|err| Self::io_error(zelf, err, vm))?;
+                let n = match handle.read(&mut bytes) {
+                    Ok(n) => n,
+                    Err(ref err) if Self::is_would_block(err) => return Ok(None),
+                    Err(err) => return Err(Self::io_error(zelf, err, vm)),
+                };
                 bytes.truncate(n);
                 bytes
             } else {
-                let mut bytes = vec![];
-                handle
-                    .read_to_end(&mut bytes)
-                    .map_err(|err| Self::io_error(zelf, err, vm))?;
+                // Slurp the rest of the file in blksize-aligned reads so we issue one
+                // syscall per optimal transfer unit instead of libstd's generic
+                // doubling, stopping at the first short/zero read.
+                let blksize = zelf.blksize.load().max(1) as usize;
+                let mut bytes: Vec<u8> = Vec::new();
+                loop {
+                    let filled = bytes.len();
+                    bytes.resize(filled + blksize, 0);
+                    let n = match handle.read(&mut bytes[filled..]) {
+                        Ok(n) => n,
+                        // Nothing read yet: signal "try again"; otherwise return the
+                        // bytes gathered so far.
+                        Err(ref err) if Self::is_would_block(err) => {
+                            bytes.truncate(filled);
+                            if filled == 0 {
+                                return Ok(None);
+                            }
+                            break;
+                        }
+                        Err(err) => return Err(Self::io_error(zelf, err, vm)),
+                    };
+                    bytes.truncate(filled + n);
+                    if n == 0 {
+                        break;
+                    }
+                }
                 bytes
             };
 
-            Ok(bytes)
+            Ok(Some(bytes))
+        }
+
+        #[pygetset]
+        fn _blksize(&self) -> i64 {
+            self.blksize.load()
         }
 
         #[pymethod]
-        fn readinto(zelf: &Py<Self>, obj: ArgMemoryBuffer, vm: &VirtualMachine) -> PyResult<usize> {
+        fn readinto(
+            zelf: &Py<Self>,
+            obj: ArgMemoryBuffer,
+            vm: &VirtualMachine,
+        ) -> PyResult<Option<usize>> {
             if !zelf.mode.load().contains(Mode::READABLE) {
                 return Err(new_unsupported_operation(
                     vm,
@@ -4519,15 +5302,19 @@ mod fileio {
 
             let mut buf = obj.borrow_buf_mut();
             let mut f = handle.take(buf.len() as _);
-            let ret = f
-                .read(&mut buf)
-                .map_err(|err| Self::io_error(zelf, err, vm))?;
-
-            Ok(ret)
+            match f.read(&mut buf) {
+                Ok(ret) => Ok(Some(ret)),
+                Err(ref err) if Self::is_would_block(err) => Ok(None),
+                Err(err) => Err(Self::io_error(zelf, err, vm)),
+            }
         }
 
         #[pymethod]
-        fn write(zelf: &Py<Self>, obj: ArgBytesLike, vm: &VirtualMachine) -> PyResult<usize> {
+        fn write(
+            zelf: &Py<Self>,
+            obj: ArgBytesLike,
+            vm: &VirtualMachine,
+        ) -> PyResult<Option<usize>> {
             if !zelf.mode.load().contains(Mode::WRITABLE) {
                 return Err(new_unsupported_operation(
                     vm,
@@ -4537,12 +5324,210 @@ mod fileio {
 
             let mut handle = zelf.get_fd(vm)?;
 
-            let len = obj
-                .with_ref(|b| handle.write(b))
+            //return number of bytes written
+            match obj.with_ref(|b| handle.write(b)) {
+                Ok(len) => Ok(Some(len)),
+                Err(ref err) if Self::is_would_block(err) => Ok(None),
+                Err(err) => Err(Self::io_error(zelf, err, vm)),
+            }
+        }
+
+        /// Read `size` bytes starting at `offset` without moving the file cursor.
+        #[pymethod]
+        fn pread(
+            zelf: &Py<Self>,
+            size: usize,
+            offset: Offset,
+            vm: &VirtualMachine,
+        ) -> PyResult<Vec<u8>> {
+            if !zelf.mode.load().contains(Mode::READABLE) {
+                return Err(new_unsupported_operation(
+                    vm,
+                    "File or stream is not readable".to_owned(),
+                ));
+            }
+            if offset < 0 {
+                return Err(vm.new_value_error("offset must be non-negative"));
+            }
+            let fd = zelf.fileno(vm)?;
+            let mut buf = vec![0u8; size];
+            let n = Self::pread_raw(fd, &mut buf, offset)
                 .map_err(|err| Self::io_error(zelf, err, vm))?;
+            buf.truncate(n);
+            Ok(buf)
+        }
 
-            //return number of bytes written
-            Ok(len)
+        /// Write `obj` starting at `offset` without moving the file cursor.
+        #[pymethod]
+        fn pwrite(
+            zelf: &Py<Self>,
+            obj: ArgBytesLike,
+            offset: Offset,
+            vm: &VirtualMachine,
+        ) -> PyResult<usize> {
+            if !zelf.mode.load().contains(Mode::WRITABLE) {
+                return Err(new_unsupported_operation(
+                    vm,
+                    "File or stream is not writable".to_owned(),
+                ));
+            }
+            if offset < 0 {
+                return Err(vm.new_value_error("offset must be non-negative"));
+            }
+            let fd = zelf.fileno(vm)?;
+            obj.with_ref(|b| Self::pwrite_raw(fd, b, offset))
+                .map_err(|err| Self::io_error(zelf, err, vm))
+        }
+
+        #[cfg(any(unix, target_os = "wasi"))]
+        fn pread_raw(fd: i32, buf: &mut [u8], offset: Offset) -> std::io::Result<usize> {
+            let ret = unsafe {
+                libc::pread(fd, buf.as_mut_ptr().cast(), buf.len() as _, offset as _)
+            };
+            if ret < 0 {
+                Err(std::io::Error::last_os_error())
+            } else {
+                Ok(ret as usize)
+            }
+        }
+
+        #[cfg(any(unix, target_os = "wasi"))]
+        fn pwrite_raw(fd: i32, buf: &[u8], offset: Offset) -> std::io::Result<usize> {
+            let ret =
+                unsafe { libc::pwrite(fd, buf.as_ptr().cast(), buf.len() as _, offset as _) };
+            if ret < 0 {
+                Err(std::io::Error::last_os_error())
+            } else {
+                Ok(ret as usize)
+            }
+        }
+
+        // Windows lacks pread/pwrite, so emulate them by saving the cursor, seeking to
+        // the explicit offset for the one operation, then restoring it.
+        #[cfg(windows)]
+        fn pread_raw(fd: i32, buf: &mut [u8], offset: Offset) -> std::io::Result<usize> {
+            let saved = unsafe { libc::lseek(fd, 0, libc::SEEK_CUR) };
+            unsafe { libc::lseek(fd, offset as _, libc::SEEK_SET) };
+            let ret = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len() as _) };
+            unsafe { libc::lseek(fd, saved, libc::SEEK_SET) };
+            if ret < 0 {
+                Err(std::io::Error::last_os_error())
+            } else {
+                Ok(ret as usize)
+            }
+        }
+
+        #[cfg(windows)]
+        fn pwrite_raw(fd: i32, buf: &[u8], offset: Offset) -> std::io::Result<usize> {
+            let saved = unsafe { libc::lseek(fd, 0, libc::SEEK_CUR) };
+            unsafe { libc::lseek(fd, offset as _, libc::SEEK_SET) };
+            let ret = unsafe { libc::write(fd, buf.as_ptr().cast(), buf.len() as _) };
+            unsafe { libc::lseek(fd, saved, libc::SEEK_SET) };
+            if ret < 0 {
+                Err(std::io::Error::last_os_error())
+            } else {
+                Ok(ret as usize)
+            }
+        }
+
+        /// Scatter read: fill each buffer in `bufs` in turn with a single `readv`
+        /// syscall where available, falling back to successive `read`s otherwise.
+        #[pymethod]
+        fn readv(zelf: &Py<Self>, bufs: Vec<ArgMemoryBuffer>, vm: &VirtualMachine) -> PyResult<usize> {
+            if !zelf.mode.load().contains(Mode::READABLE) {
+                return Err(new_unsupported_operation(
+                    vm,
+                    "File or stream is not readable".to_owned(),
+                ));
+            }
+            // Borrowing the same buffer object mutably twice (e.g. `f.readv([b, b])`)
+            // would double-borrow its underlying lock and panic; reject the aliasing
+            // instead of trusting the caller not to pass overlapping buffers.
+            for (i, a) in bufs.iter().enumerate() {
+                if bufs[..i].iter().any(|b| a.obj.is(&b.obj)) {
+                    return Err(vm.new_value_error("readv(): buffer objects must not overlap"));
+                }
+            }
+            let fd = zelf.fileno(vm)?;
+            let mut guards: Vec<_> = bufs.iter().map(|b| b.borrow_buf_mut()).collect();
+            #[cfg(unix)]
+            {
+                let mut iovs: Vec<libc::iovec> = guards
+                    .iter_mut()
+                    .map(|g| libc::iovec {
+                        iov_base: g.as_mut_ptr().cast(),
+                        iov_len: g.len() as _,
+                    })
+                    .collect();
+                let ret =
+                    unsafe { libc::readv(fd, iovs.as_mut_ptr(), iovs.len() as _) };
+                if ret < 0 {
+                    Err(Self::io_error(zelf, std::io::Error::last_os_error(), vm))
+                } else {
+                    Ok(ret as usize)
+                }
+            }
+            #[cfg(not(unix))]
+            {
+                let mut handle = Fd(fd);
+                let mut total = 0;
+                for g in &mut guards {
+                    let n = handle
+                        .read(g)
+                        .map_err(|err| Self::io_error(zelf, err, vm))?;
+                    total += n;
+                    if n < g.len() {
+                        break;
+                    }
+                }
+                Ok(total)
+            }
+        }
+
+        /// Gather write: emit every buffer in `bufs` with a single `writev` syscall
+        /// where available, falling back to successive `write`s otherwise.
+        #[pymethod]
+        fn writev(zelf: &Py<Self>, bufs: Vec<ArgBytesLike>, vm: &VirtualMachine) -> PyResult<usize> {
+            if !zelf.mode.load().contains(Mode::WRITABLE) {
+                return Err(new_unsupported_operation(
+                    vm,
+                    "File or stream is not writable".to_owned(),
+                ));
+            }
+            let fd = zelf.fileno(vm)?;
+            let guards: Vec<_> = bufs.iter().map(|b| b.borrow_buf()).collect();
+            #[cfg(unix)]
+            {
+                let iovs: Vec<libc::iovec> = guards
+                    .iter()
+                    .map(|g| libc::iovec {
+                        iov_base: g.as_ptr() as *mut _,
+                        iov_len: g.len() as _,
+                    })
+                    .collect();
+                let ret =
+                    unsafe { libc::writev(fd, iovs.as_ptr(), iovs.len() as _) };
+                if ret < 0 {
+                    Err(Self::io_error(zelf, std::io::Error::last_os_error(), vm))
+                } else {
+                    Ok(ret as usize)
+                }
+            }
+            #[cfg(not(unix))]
+            {
+                let mut handle = Fd(fd);
+                let mut total = 0;
+                for g in &guards {
+                    let n = handle
+                        .write(g)
+                        .map_err(|err| Self::io_error(zelf, err, vm))?;
+                    total += n;
+                    if n < g.len() {
+                        break;
+                    }
+                }
+                Ok(total)
+            }
         }
 
         #[pymethod]
@@ -7,8 +7,8 @@ mod decl {
         AsObject, Py, PyObjectRef, PyPayload, PyRef, PyResult, PyWeakRef, TryFromObject,
         VirtualMachine,
         builtins::{
-            PyGenericAlias, PyInt, PyIntRef, PyList, PyTuple, PyTupleRef, PyTypeRef, int,
-            tuple::IntoPyTuple,
+            PyDictRef, PyGenericAlias, PyInt, PyIntRef, PyList, PyTuple, PyTupleRef, PyTypeRef,
+            int, tuple::IntoPyTuple,
         },
         common::{
             lock::{PyMutex, PyRwLock, PyRwLockWriteGuard},
@@ -20,7 +20,7 @@ mod decl {
         protocol::{PyIter, PyIterReturn, PyNumber},
         raise_if_stop,
         stdlib::sys,
-        types::{Constructor, IterNext, Iterable, Representable, SelfIter},
+        types::{Constructor, IterNext, Iterable, PyComparisonOp, Representable, SelfIter},
     };
     use crossbeam_utils::atomic::AtomicCell;
     use malachite_bigint::BigInt;
@@ -270,12 +270,12 @@ mod decl {
 
     #[pyclass(with(IterNext, Iterable, Constructor, Representable))]
     impl PyItertoolsCount {
-        // TODO: Implement this
-        // if (lz->cnt == PY_SSIZE_T_MAX)
-        //      return Py_BuildValue("0(00)", Py_TYPE(lz), lz->long_cnt, lz->long_step);
         #[pymethod]
-        fn __reduce__(zelf: PyRef<Self>) -> (PyTypeRef, (PyObjectRef,)) {
-            (zelf.class().to_owned(), (zelf.cur.read().clone(),))
+        fn __reduce__(zelf: PyRef<Self>) -> (PyTypeRef, (PyObjectRef, PyObjectRef)) {
+            (
+                zelf.class().to_owned(),
+                (zelf.cur.read().clone(), zelf.step.clone()),
+            )
         }
     }
 
@@ -666,6 +666,300 @@ mod decl {
         }
     }
 
+    #[pyattr]
+    #[pyclass(name = "coalesce")]
+    #[derive(Debug, PyPayload)]
+    struct PyItertoolsCoalesce {
+        iterable: PyIter,
+        function: PyObjectRef,
+        held: PyRwLock<Option<PyObjectRef>>,
+    }
+
+    #[derive(FromArgs)]
+    struct CoalesceNewArgs {
+        #[pyarg(positional)]
+        iterable: PyIter,
+        #[pyarg(positional)]
+        function: PyObjectRef,
+    }
+
+    impl Constructor for PyItertoolsCoalesce {
+        type Args = CoalesceNewArgs;
+
+        fn py_new(
+            cls: PyTypeRef,
+            Self::Args { iterable, function }: Self::Args,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            Self {
+                iterable,
+                function,
+                held: PyRwLock::new(None),
+            }
+            .into_ref_with_type(vm, cls)
+            .map(Into::into)
+        }
+    }
+
+    #[pyclass(with(IterNext, Iterable, Constructor), flags(BASETYPE))]
+    impl PyItertoolsCoalesce {
+        #[pymethod]
+        fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyTupleRef {
+            let cls = zelf.class().to_owned();
+            let args = vm.new_tuple((zelf.iterable.clone(), zelf.function.clone()));
+            match zelf.held.read().clone() {
+                Some(held) => vm.new_tuple((cls, args, (held,))),
+                None => vm.new_tuple((cls, args)),
+            }
+        }
+
+        #[pymethod]
+        fn __setstate__(zelf: PyRef<Self>, state: PyTupleRef, vm: &VirtualMachine) -> PyResult<()> {
+            let args = state.as_slice();
+            if args.len() != 1 {
+                return Err(vm.new_type_error(format!(
+                    "function takes exactly 1 argument ({} given)",
+                    args.len()
+                )));
+            }
+            *zelf.held.write() = Some(args[0].clone());
+            Ok(())
+        }
+    }
+
+    impl SelfIter for PyItertoolsCoalesce {}
+
+    impl IterNext for PyItertoolsCoalesce {
+        fn next(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyIterReturn> {
+            let mut held = zelf.held.write();
+            if held.is_none() {
+                *held = Some(raise_if_stop!(zelf.iterable.next(vm)?));
+            }
+            loop {
+                let b = match zelf.iterable.next(vm)? {
+                    PyIterReturn::Return(obj) => obj,
+                    PyIterReturn::StopIteration(_) => {
+                        return Ok(PyIterReturn::Return(held.take().unwrap()));
+                    }
+                };
+                let acc = held.as_ref().unwrap().clone();
+                let result = zelf.function.call((acc, b), vm)?;
+                let result = PyTupleRef::try_from_object(vm, result)?;
+                let parts = result.as_slice();
+                if parts.len() != 2 {
+                    return Err(vm.new_value_error(
+                        "coalesce function must return a (merged, value) 2-tuple",
+                    ));
+                }
+                let merged = parts[0].clone().try_to_bool(vm)?;
+                let value = parts[1].clone();
+                if merged {
+                    *held = Some(value);
+                } else {
+                    let out = held.take().unwrap();
+                    *held = Some(value);
+                    return Ok(PyIterReturn::Return(out));
+                }
+            }
+        }
+    }
+
+    #[pyattr]
+    #[pyclass(name = "unique")]
+    #[derive(Debug, PyPayload)]
+    struct PyItertoolsUnique {
+        iterable: PyIter,
+        key_func: Option<PyObjectRef>,
+        seen: PyDictRef,
+        /// Keys that raised `TypeError` out of `seen`'s hashing, scanned
+        /// linearly with `__eq__` — the fallback path for unhashable
+        /// objects, matching the CPython `unique_everseen` recipe's
+        /// two-path design.
+        seen_unhashable: PyRwLock<Vec<PyObjectRef>>,
+    }
+
+    #[derive(FromArgs)]
+    struct UniqueNewArgs {
+        #[pyarg(positional)]
+        iterable: PyIter,
+        #[pyarg(any, optional)]
+        key: OptionalOption<PyObjectRef>,
+    }
+
+    impl Constructor for PyItertoolsUnique {
+        type Args = UniqueNewArgs;
+
+        fn py_new(
+            cls: PyTypeRef,
+            Self::Args { iterable, key }: Self::Args,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            Self {
+                iterable,
+                key_func: key.flatten(),
+                seen: vm.ctx.new_dict(),
+                seen_unhashable: PyRwLock::new(Vec::new()),
+            }
+            .into_ref_with_type(vm, cls)
+            .map(Into::into)
+        }
+    }
+
+    #[pyclass(with(IterNext, Iterable, Constructor), flags(BASETYPE))]
+    impl PyItertoolsUnique {
+        #[pymethod]
+        fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyTupleRef {
+            let cls = zelf.class().to_owned();
+            let key = zelf.key_func.clone().unwrap_or_else(|| vm.ctx.none());
+            let args = vm.new_tuple((zelf.iterable.clone(), key));
+            let seen: Vec<PyObjectRef> = zelf.seen.into_iter().map(|(k, _)| k).collect();
+            let seen_unhashable = zelf.seen_unhashable.read().clone();
+            vm.new_tuple((
+                cls,
+                args,
+                (seen.into_pytuple(vm), seen_unhashable.into_pytuple(vm)),
+            ))
+        }
+
+        #[pymethod]
+        fn __setstate__(zelf: PyRef<Self>, state: PyTupleRef, vm: &VirtualMachine) -> PyResult<()> {
+            let args = state.as_slice();
+            if args.len() != 2 {
+                return Err(vm.new_type_error(format!(
+                    "function takes exactly 2 arguments ({} given)",
+                    args.len()
+                )));
+            }
+            let seen = PyTupleRef::try_from_object(vm, args[0].clone())?;
+            for item in seen.as_slice() {
+                zelf.seen.set_item(&**item, vm.ctx.none(), vm)?;
+            }
+            let seen_unhashable = PyTupleRef::try_from_object(vm, args[1].clone())?;
+            zelf.seen_unhashable
+                .write()
+                .extend(seen_unhashable.as_slice().iter().cloned());
+            Ok(())
+        }
+    }
+
+    impl SelfIter for PyItertoolsUnique {}
+
+    impl IterNext for PyItertoolsUnique {
+        fn next(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyIterReturn> {
+            'outer: loop {
+                let obj = raise_if_stop!(zelf.iterable.next(vm)?);
+                let key = match &zelf.key_func {
+                    Some(f) => f.call((obj.clone(),), vm)?,
+                    None => obj.clone(),
+                };
+                match zelf.seen.get_item_opt(&*key, vm) {
+                    Ok(Some(_)) => continue 'outer,
+                    Ok(None) => {
+                        zelf.seen.set_item(&*key, vm.ctx.none(), vm)?;
+                        return Ok(PyIterReturn::Return(obj));
+                    }
+                    // Unhashable key: fall back to a linear `__eq__` scan,
+                    // mirroring CPython's `unique_everseen` recipe.
+                    Err(e) if e.fast_isinstance(vm.ctx.exceptions.type_error) => {
+                        let mut seen_unhashable = zelf.seen_unhashable.write();
+                        for seen_key in seen_unhashable.iter() {
+                            if vm.bool_eq(seen_key, &key)? {
+                                continue 'outer;
+                            }
+                        }
+                        seen_unhashable.push(key);
+                        return Ok(PyIterReturn::Return(obj));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+
+    #[pyattr]
+    #[pyclass(name = "duplicates")]
+    #[derive(Debug, PyPayload)]
+    struct PyItertoolsDuplicates {
+        iterable: PyIter,
+        key_func: Option<PyObjectRef>,
+        seen: PyDictRef,
+        emitted: PyDictRef,
+    }
+
+    impl Constructor for PyItertoolsDuplicates {
+        type Args = UniqueNewArgs;
+
+        fn py_new(
+            cls: PyTypeRef,
+            Self::Args { iterable, key }: Self::Args,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            Self {
+                iterable,
+                key_func: key.flatten(),
+                seen: vm.ctx.new_dict(),
+                emitted: vm.ctx.new_dict(),
+            }
+            .into_ref_with_type(vm, cls)
+            .map(Into::into)
+        }
+    }
+
+    #[pyclass(with(IterNext, Iterable, Constructor), flags(BASETYPE))]
+    impl PyItertoolsDuplicates {
+        #[pymethod]
+        fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyTupleRef {
+            let cls = zelf.class().to_owned();
+            let key = zelf.key_func.clone().unwrap_or_else(|| vm.ctx.none());
+            let args = vm.new_tuple((zelf.iterable.clone(), key));
+            let seen: Vec<PyObjectRef> = zelf.seen.into_iter().map(|(k, _)| k).collect();
+            let emitted: Vec<PyObjectRef> = zelf.emitted.into_iter().map(|(k, _)| k).collect();
+            vm.new_tuple((cls, args, (seen.into_pytuple(vm), emitted.into_pytuple(vm))))
+        }
+
+        #[pymethod]
+        fn __setstate__(zelf: PyRef<Self>, state: PyTupleRef, vm: &VirtualMachine) -> PyResult<()> {
+            let args = state.as_slice();
+            if args.len() != 2 {
+                return Err(vm.new_type_error(format!(
+                    "function takes exactly 2 arguments ({} given)",
+                    args.len()
+                )));
+            }
+            let seen = PyTupleRef::try_from_object(vm, args[0].clone())?;
+            for item in seen.as_slice() {
+                zelf.seen.set_item(&**item, vm.ctx.none(), vm)?;
+            }
+            let emitted = PyTupleRef::try_from_object(vm, args[1].clone())?;
+            for item in emitted.as_slice() {
+                zelf.emitted.set_item(&**item, vm.ctx.none(), vm)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl SelfIter for PyItertoolsDuplicates {}
+
+    impl IterNext for PyItertoolsDuplicates {
+        fn next(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyIterReturn> {
+            loop {
+                let obj = raise_if_stop!(zelf.iterable.next(vm)?);
+                let key = match &zelf.key_func {
+                    Some(f) => f.call((obj.clone(),), vm)?,
+                    None => obj.clone(),
+                };
+                if zelf.seen.get_item_opt(&*key, vm)?.is_some() {
+                    if zelf.emitted.get_item_opt(&*key, vm)?.is_some() {
+                        continue;
+                    }
+                    zelf.emitted.set_item(&*key, vm.ctx.none(), vm)?;
+                    return Ok(PyIterReturn::Return(obj));
+                }
+                zelf.seen.set_item(&*key, vm.ctx.none(), vm)?;
+            }
+        }
+    }
+
     #[derive(Default)]
     struct GroupByState {
         current_value: Option<PyObjectRef>,
@@ -837,6 +1131,195 @@ mod decl {
         }
     }
 
+    #[pyattr]
+    #[pyclass(name = "grouping_map")]
+    #[derive(Debug, PyPayload)]
+    struct PyItertoolsGroupingMap {
+        groups: PyDictRef,
+    }
+
+    impl Constructor for PyItertoolsGroupingMap {
+        type Args = PyIter;
+
+        fn py_new(cls: PyTypeRef, iterable: Self::Args, vm: &VirtualMachine) -> PyResult {
+            let groups = vm.ctx.new_dict();
+            while let PyIterReturn::Return(item) = iterable.next(vm)? {
+                let pair = PyTupleRef::try_from_object(vm, item)?;
+                let parts = pair.as_slice();
+                if parts.len() != 2 {
+                    return Err(vm.new_value_error("grouping_map expects an iterable of (key, value) pairs"));
+                }
+                PyItertoolsGroupingMap::insert_into(&groups, parts[0].clone(), parts[1].clone(), vm)?;
+            }
+            Self { groups }.into_ref_with_type(vm, cls).map(Into::into)
+        }
+    }
+
+    #[pyclass(with(Constructor), flags(BASETYPE))]
+    impl PyItertoolsGroupingMap {
+        /// Append `value` to `key`'s bucket in `groups`, creating it on
+        /// first sight. Shared by the `Constructor` (which takes ready-made
+        /// `(key, value)` pairs) and the [`grouping_map`] function (which
+        /// computes the key itself as each item arrives).
+        fn insert_into(
+            groups: &PyDictRef,
+            key: PyObjectRef,
+            value: PyObjectRef,
+            vm: &VirtualMachine,
+        ) -> PyResult<()> {
+            match groups.get_item_opt(&*key, vm)? {
+                Some(bucket) => {
+                    let bucket = bucket
+                        .downcast::<PyList>()
+                        .map_err(|_| vm.new_type_error("grouping_map bucket corrupted"))?;
+                    bucket.borrow_vec_mut().push(value);
+                }
+                None => {
+                    groups.set_item(&*key, PyList::from(vec![value]).into_pyobject(vm), vm)?;
+                }
+            }
+            Ok(())
+        }
+
+        fn buckets(&self) -> Vec<(PyObjectRef, PyRef<PyList>)> {
+            self.groups
+                .into_iter()
+                .map(|(key, value)| {
+                    let bucket = value
+                        .downcast::<PyList>()
+                        .expect("grouping_map buckets are always lists");
+                    (key, bucket)
+                })
+                .collect()
+        }
+
+        #[pymethod]
+        fn collect(&self, vm: &VirtualMachine) -> PyDictRef {
+            let out = vm.ctx.new_dict();
+            for (key, bucket) in self.buckets() {
+                out.set_item(&*key, bucket.to_pyobject(vm), vm).unwrap();
+            }
+            out
+        }
+
+        #[pymethod]
+        fn fold(&self, init: PyObjectRef, func: ArgCallable, vm: &VirtualMachine) -> PyResult<PyDictRef> {
+            let out = vm.ctx.new_dict();
+            for (key, bucket) in self.buckets() {
+                let mut acc = init.clone();
+                for value in bucket.borrow_vec().iter() {
+                    acc = func.invoke((acc, value.clone()), vm)?;
+                }
+                out.set_item(&*key, acc, vm)?;
+            }
+            Ok(out)
+        }
+
+        #[pymethod]
+        fn reduce(&self, func: ArgCallable, vm: &VirtualMachine) -> PyResult<PyDictRef> {
+            let out = vm.ctx.new_dict();
+            for (key, bucket) in self.buckets() {
+                let values = bucket.borrow_vec();
+                let mut values = values.iter();
+                let mut acc = values
+                    .next()
+                    .ok_or_else(|| vm.new_value_error("reduce() of empty group"))?
+                    .clone();
+                for value in values {
+                    acc = func.invoke((acc, key.clone(), value.clone()), vm)?;
+                }
+                out.set_item(&*key, acc, vm)?;
+            }
+            Ok(out)
+        }
+
+        #[pymethod]
+        fn aggregate(&self, func: ArgCallable, vm: &VirtualMachine) -> PyResult<PyDictRef> {
+            let out = vm.ctx.new_dict();
+            for (key, bucket) in self.buckets() {
+                let mut acc = vm.ctx.none();
+                for value in bucket.borrow_vec().iter() {
+                    acc = func.invoke((acc, key.clone(), value.clone()), vm)?;
+                }
+                out.set_item(&*key, acc, vm)?;
+            }
+            Ok(out)
+        }
+
+        #[pymethod]
+        fn sum(&self, vm: &VirtualMachine) -> PyResult<PyDictRef> {
+            let out = vm.ctx.new_dict();
+            for (key, bucket) in self.buckets() {
+                let values = bucket.borrow_vec();
+                let mut values = values.iter();
+                let mut acc = values
+                    .next()
+                    .ok_or_else(|| vm.new_value_error("sum() of empty group"))?
+                    .clone();
+                for value in values {
+                    acc = vm._add(&acc, value)?;
+                }
+                out.set_item(&*key, acc, vm)?;
+            }
+            Ok(out)
+        }
+
+        #[pymethod]
+        fn max(&self, vm: &VirtualMachine) -> PyResult<PyDictRef> {
+            let out = vm.ctx.new_dict();
+            for (key, bucket) in self.buckets() {
+                let values = bucket.borrow_vec();
+                let mut values = values.iter();
+                let mut acc = values
+                    .next()
+                    .ok_or_else(|| vm.new_value_error("max() of empty group"))?
+                    .clone();
+                for value in values {
+                    if vm.bool_cmp(value, &acc, PyComparisonOp::Gt)? {
+                        acc = value.clone();
+                    }
+                }
+                out.set_item(&*key, acc, vm)?;
+            }
+            Ok(out)
+        }
+
+        #[pymethod]
+        fn min(&self, vm: &VirtualMachine) -> PyResult<PyDictRef> {
+            let out = vm.ctx.new_dict();
+            for (key, bucket) in self.buckets() {
+                let values = bucket.borrow_vec();
+                let mut values = values.iter();
+                let mut acc = values
+                    .next()
+                    .ok_or_else(|| vm.new_value_error("min() of empty group"))?
+                    .clone();
+                for value in values {
+                    if vm.bool_cmp(value, &acc, PyComparisonOp::Lt)? {
+                        acc = value.clone();
+                    }
+                }
+                out.set_item(&*key, acc, vm)?;
+            }
+            Ok(out)
+        }
+
+        #[pymethod]
+        fn count(&self, vm: &VirtualMachine) -> PyDictRef {
+            let out = vm.ctx.new_dict();
+            for (key, bucket) in self.buckets() {
+                out.set_item(&*key, vm.new_pyobj(bucket.borrow_vec().len()), vm)
+                    .unwrap();
+            }
+            out
+        }
+
+        #[pymethod]
+        fn collect_list(&self, vm: &VirtualMachine) -> PyDictRef {
+            self.collect(vm)
+        }
+    }
+
     #[pyattr]
     #[pyclass(name = "islice")]
     #[derive(Debug, PyPayload)]
@@ -1177,31 +1660,63 @@ mod decl {
         }
     }
 
+    /// Cells per [`TeeNode`], matching CPython's `teedataobject` — small
+    /// enough that a fully-consumed node is reclaimed promptly, large enough
+    /// that a long tee chain doesn't turn into a pointer-chasing linked list
+    /// of singletons.
+    const TEE_NODE_CAPACITY: usize = 57;
+
+    /// One fixed-size link in the chain of values pulled from a `tee`'s
+    /// shared source iterator. A [`PyItertoolsTee`] drops its `PyRc` to a
+    /// node as soon as it advances past it, so once every tee has moved on,
+    /// that node (and the values it holds) is freed instead of living for
+    /// the lifetime of the whole tee the way one ever-growing `Vec` would.
+    #[derive(Debug)]
+    struct TeeNode {
+        values: PyRwLock<Vec<PyObjectRef>>,
+        next: PyRwLock<Option<PyRc<TeeNode>>>,
+    }
+
+    impl TeeNode {
+        fn new() -> PyRc<Self> {
+            PyRc::new(Self {
+                values: PyRwLock::new(Vec::with_capacity(TEE_NODE_CAPACITY)),
+                next: PyRwLock::new(None),
+            })
+        }
+    }
+
     #[derive(Debug)]
     struct PyItertoolsTeeData {
         iterable: PyIter,
-        values: PyMutex<Vec<PyObjectRef>>,
+        /// The node currently being filled from `iterable`. Every tee shares
+        /// this same mutex to serialize pulls.
+        growing: PyMutex<PyRc<TeeNode>>,
     }
 
     impl PyItertoolsTeeData {
         fn new(iterable: PyIter, _vm: &VirtualMachine) -> PyResult<PyRc<Self>> {
             Ok(PyRc::new(Self {
                 iterable,
-                values: PyMutex::new(vec![]),
+                growing: PyMutex::new(TeeNode::new()),
             }))
         }
 
-        fn get_item(&self, vm: &VirtualMachine, index: usize) -> PyResult<PyIterReturn> {
-            let Some(mut values) = self.values.try_lock() else {
+        /// Pull one more value from the source iterator into whichever node
+        /// is currently being grown, linking a fresh node once it's full.
+        fn pull(&self, vm: &VirtualMachine) -> PyResult<PyIterReturn<()>> {
+            let Some(mut growing) = self.growing.try_lock() else {
                 return Err(vm.new_runtime_error("cannot re-enter the tee iterator"));
             };
-
-            if values.len() == index {
-                let obj = raise_if_stop!(self.iterable.next(vm)?);
-                values.push(obj);
+            let node = PyRc::clone(&growing);
+            let obj = raise_if_stop!(self.iterable.next(vm)?);
+            node.values.write().push(obj);
+            if node.values.read().len() == TEE_NODE_CAPACITY {
+                let next = TeeNode::new();
+                *node.next.write() = Some(PyRc::clone(&next));
+                *growing = next;
             }
-
-            Ok(PyIterReturn::Return(values[index].clone()))
+            Ok(PyIterReturn::Return(()))
         }
     }
 
@@ -1210,6 +1725,7 @@ mod decl {
     #[derive(Debug, PyPayload)]
     struct PyItertoolsTee {
         tee_data: PyRc<PyItertoolsTeeData>,
+        node: PyRwLock<PyRc<TeeNode>>,
         index: AtomicCell<usize>,
     }
 
@@ -1255,8 +1771,11 @@ mod decl {
             if iterator.class().is(Self::class(&vm.ctx)) {
                 return vm.call_special_method(&iterator, identifier!(vm, __copy__), ());
             }
+            let tee_data = PyItertoolsTeeData::new(iterator, vm)?;
+            let node = PyRc::clone(&tee_data.growing.lock());
             Ok(Self {
-                tee_data: PyItertoolsTeeData::new(iterator, vm)?,
+                tee_data,
+                node: PyRwLock::new(node),
                 index: AtomicCell::new(0),
             }
             .into_ref_with_type(vm, class.to_owned())?
@@ -1267,6 +1786,7 @@ mod decl {
         fn __copy__(&self) -> Self {
             Self {
                 tee_data: PyRc::clone(&self.tee_data),
+                node: PyRwLock::new(PyRc::clone(&self.node.read())),
                 index: AtomicCell::new(self.index.load()),
             }
         }
@@ -1274,12 +1794,34 @@ mod decl {
     impl SelfIter for PyItertoolsTee {}
     impl IterNext for PyItertoolsTee {
         fn next(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyIterReturn> {
-            let value = raise_if_stop!(zelf.tee_data.get_item(vm, zelf.index.load())?);
-            zelf.index.fetch_add(1);
-            Ok(PyIterReturn::Return(value))
-        }
-    }
-
+            loop {
+                let local = zelf.index.load();
+                if local == TEE_NODE_CAPACITY {
+                    // `pull` links a node's `next` the moment that node fills
+                    // up, so a node can only reach `local == CAPACITY` here
+                    // once its successor already exists.
+                    let next_node = zelf
+                        .node
+                        .read()
+                        .next
+                        .read()
+                        .clone()
+                        .expect("a full tee node always has its next node linked");
+                    *zelf.node.write() = next_node;
+                    zelf.index.store(0);
+                    continue;
+                }
+
+                if let Some(value) = zelf.node.read().values.read().get(local).cloned() {
+                    zelf.index.store(local + 1);
+                    return Ok(PyIterReturn::Return(value));
+                }
+
+                raise_if_stop!(zelf.tee_data.pull(vm)?);
+            }
+        }
+    }
+
     #[pyattr]
     #[pyclass(name = "product")]
     #[derive(Debug, PyPayload)]
@@ -1430,6 +1972,44 @@ mod decl {
         }
     }
 
+    /// The binomial coefficient `C(n, r)`, `0` when `r > n`. Computed
+    /// incrementally in `BigInt` (each partial product stays exact, since
+    /// `C(n, k) * (n-k) / (k+1) == C(n, k+1)`) so it can't overflow before
+    /// being clamped down to a `usize` length hint.
+    fn binomial(n: usize, r: usize) -> BigInt {
+        if r > n {
+            return BigInt::from(0);
+        }
+        let r = r.min(n - r);
+        let mut acc = BigInt::from(1);
+        for k in 0..r {
+            acc = acc * BigInt::from(n - k) / BigInt::from(k + 1);
+        }
+        acc
+    }
+
+    /// The falling factorial `n! / (n-r)!`, the count of `r`-permutations of
+    /// an `n`-element pool. `0` when `r > n`, the same way `binomial` treats
+    /// an oversized `r` -- there's no way to choose more elements than the
+    /// pool has, and `n - k` would otherwise underflow `usize` partway
+    /// through the product.
+    fn falling_factorial(n: usize, r: usize) -> BigInt {
+        if r > n {
+            return BigInt::from(0);
+        }
+        let mut acc = BigInt::from(1);
+        for k in 0..r {
+            acc *= BigInt::from(n - k);
+        }
+        acc
+    }
+
+    /// `__length_hint__`'s contract only promises a `usize`, so an exact
+    /// count too big to fit just saturates rather than wrapping.
+    fn length_hint_saturating(count: BigInt) -> usize {
+        count.to_usize().unwrap_or(usize::MAX)
+    }
+
     #[pyattr]
     #[pyclass(name = "combinations")]
     #[derive(Debug, PyPayload)]
@@ -1438,6 +2018,7 @@ mod decl {
         indices: PyRwLock<Vec<usize>>,
         result: PyRwLock<Option<Vec<PyObjectRef>>>,
         r: AtomicCell<usize>,
+        produced: AtomicCell<usize>,
         exhausted: AtomicCell<bool>,
     }
 
@@ -1472,6 +2053,7 @@ mod decl {
                 indices: PyRwLock::new((0..r).collect()),
                 result: PyRwLock::new(None),
                 r: AtomicCell::new(r),
+                produced: AtomicCell::new(0),
                 exhausted: AtomicCell::new(r > n),
             }
             .into_ref_with_type(vm, cls)
@@ -1481,6 +2063,13 @@ mod decl {
 
     #[pyclass(with(IterNext, Iterable, Constructor))]
     impl PyItertoolsCombinations {
+        #[pymethod]
+        fn __length_hint__(&self) -> usize {
+            let total = binomial(self.pool.len(), self.r.load());
+            let produced = BigInt::from(self.produced.load());
+            length_hint_saturating((total - produced).max(BigInt::from(0)))
+        }
+
         #[pymethod]
         fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyTupleRef {
             let r = zelf.r.load();
@@ -1523,6 +2112,7 @@ mod decl {
 
             if r == 0 {
                 zelf.exhausted.store(true);
+                zelf.produced.fetch_add(1);
                 return Ok(PyIterReturn::Return(vm.new_tuple(()).into()));
             }
 
@@ -1567,6 +2157,7 @@ mod decl {
                 res
             };
 
+            zelf.produced.fetch_add(1);
             Ok(PyIterReturn::Return(vm.ctx.new_tuple(result).into()))
         }
     }
@@ -1578,6 +2169,7 @@ mod decl {
         pool: Vec<PyObjectRef>,
         indices: PyRwLock<Vec<usize>>,
         r: AtomicCell<usize>,
+        produced: AtomicCell<usize>,
         exhausted: AtomicCell<bool>,
     }
 
@@ -1602,6 +2194,7 @@ mod decl {
                 pool,
                 indices: PyRwLock::new(vec![0; r]),
                 r: AtomicCell::new(r),
+                produced: AtomicCell::new(0),
                 exhausted: AtomicCell::new(n == 0 && r > 0),
             }
             .into_ref_with_type(vm, cls)
@@ -1610,7 +2203,43 @@ mod decl {
     }
 
     #[pyclass(with(IterNext, Iterable, Constructor))]
-    impl PyItertoolsCombinationsWithReplacement {}
+    impl PyItertoolsCombinationsWithReplacement {
+        #[pymethod]
+        fn __length_hint__(&self) -> usize {
+            let r = self.r.load();
+            let total = if r == 0 {
+                BigInt::from(1)
+            } else {
+                binomial(self.pool.len() + r - 1, r)
+            };
+            let produced = BigInt::from(self.produced.load());
+            length_hint_saturating((total - produced).max(BigInt::from(0)))
+        }
+
+        #[pymethod]
+        fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyTupleRef {
+            let r = zelf.r.load();
+
+            let class = zelf.class().to_owned();
+
+            if zelf.exhausted.load() {
+                return vm.new_tuple((
+                    class,
+                    vm.new_tuple((vm.ctx.empty_tuple.clone(), vm.ctx.new_int(r))),
+                ));
+            }
+
+            let tup = vm.new_tuple((zelf.pool.clone().into_pytuple(vm), vm.ctx.new_int(r)));
+
+            let mut indices: Vec<PyObjectRef> = Vec::new();
+
+            for item in &zelf.indices.read()[..r] {
+                indices.push(vm.new_pyobj(*item));
+            }
+
+            vm.new_tuple((class, tup, indices.into_pytuple(vm)))
+        }
+    }
 
     impl SelfIter for PyItertoolsCombinationsWithReplacement {}
 
@@ -1626,6 +2255,7 @@ mod decl {
 
             if r == 0 {
                 zelf.exhausted.store(true);
+                zelf.produced.fetch_add(1);
                 return Ok(PyIterReturn::Return(vm.new_tuple(()).into()));
             }
 
@@ -1655,10 +2285,152 @@ mod decl {
                 }
             }
 
+            zelf.produced.fetch_add(1);
             Ok(PyIterReturn::Return(res.into()))
         }
     }
 
+    #[pyattr]
+    #[pyclass(name = "powerset")]
+    #[derive(Debug, PyPayload)]
+    struct PyItertoolsPowerset {
+        pool: Vec<PyObjectRef>,
+        r: AtomicCell<usize>,
+        indices: PyRwLock<Vec<usize>>,
+        started: AtomicCell<bool>,
+        produced: AtomicCell<usize>,
+        exhausted: AtomicCell<bool>,
+    }
+
+    impl Constructor for PyItertoolsPowerset {
+        type Args = PyObjectRef;
+
+        fn py_new(cls: PyTypeRef, iterable: Self::Args, vm: &VirtualMachine) -> PyResult {
+            let pool: Vec<_> = iterable.try_to_value(vm)?;
+            Self {
+                pool,
+                r: AtomicCell::new(0),
+                indices: PyRwLock::new(Vec::new()),
+                started: AtomicCell::new(false),
+                produced: AtomicCell::new(0),
+                exhausted: AtomicCell::new(false),
+            }
+            .into_ref_with_type(vm, cls)
+            .map(Into::into)
+        }
+    }
+
+    #[pyclass(with(IterNext, Iterable, Constructor))]
+    impl PyItertoolsPowerset {
+        #[pymethod]
+        fn __length_hint__(&self) -> usize {
+            let mut total = BigInt::from(1);
+            for _ in 0..self.pool.len() {
+                total *= BigInt::from(2);
+            }
+            let produced = BigInt::from(self.produced.load());
+            length_hint_saturating((total - produced).max(BigInt::from(0)))
+        }
+
+        #[pymethod]
+        fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyTupleRef {
+            let class = zelf.class().to_owned();
+            let pool = zelf.pool.clone().into_pytuple(vm);
+            if zelf.exhausted.load() {
+                return vm.new_tuple((class, (pool,)));
+            }
+            let indices = zelf.indices.read();
+            let indices: Vec<PyObjectRef> = indices.iter().map(|&i| vm.new_pyobj(i)).collect();
+            vm.new_tuple((
+                class,
+                (pool,),
+                (
+                    zelf.r.load(),
+                    indices.into_pytuple(vm),
+                    zelf.started.load() as u32,
+                ),
+            ))
+        }
+
+        #[pymethod]
+        fn __setstate__(zelf: PyRef<Self>, state: PyTupleRef, vm: &VirtualMachine) -> PyResult<()> {
+            let args = state.as_slice();
+            if args.len() != 3 {
+                return Err(vm.new_type_error(format!(
+                    "function takes exactly 3 arguments ({} given)",
+                    args.len()
+                )));
+            }
+            let r: usize = args[0].clone().try_to_value(vm)?;
+            let indices: Vec<usize> = args[1].clone().try_to_value(vm)?;
+            let started = args[2].clone().try_to_bool(vm)?;
+            if r > zelf.pool.len() {
+                zelf.exhausted.store(true);
+                return Ok(());
+            }
+            *zelf.indices.write() = indices;
+            zelf.r.store(r);
+            zelf.started.store(started);
+            Ok(())
+        }
+    }
+
+    impl SelfIter for PyItertoolsPowerset {}
+
+    impl IterNext for PyItertoolsPowerset {
+        fn next(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyIterReturn> {
+            if zelf.exhausted.load() {
+                return Ok(PyIterReturn::StopIteration(None));
+            }
+            let n = zelf.pool.len();
+            loop {
+                let r = zelf.r.load();
+                if r > n {
+                    zelf.exhausted.store(true);
+                    return Ok(PyIterReturn::StopIteration(None));
+                }
+
+                let mut indices = zelf.indices.write();
+
+                if !zelf.started.load() {
+                    *indices = (0..r).collect();
+                    zelf.started.store(true);
+                    let result: Vec<_> = indices.iter().map(|&i| zelf.pool[i].clone()).collect();
+                    zelf.produced.fetch_add(1);
+                    return Ok(PyIterReturn::Return(vm.ctx.new_tuple(result).into()));
+                }
+
+                if r == 0 {
+                    drop(indices);
+                    zelf.r.fetch_add(1);
+                    zelf.started.store(false);
+                    continue;
+                }
+
+                // Scan indices right-to-left until finding one that is not at its maximum (i + n - r).
+                let mut idx = r as isize - 1;
+                while idx >= 0 && indices[idx as usize] == idx as usize + n - r {
+                    idx -= 1;
+                }
+
+                if idx < 0 {
+                    drop(indices);
+                    zelf.r.fetch_add(1);
+                    zelf.started.store(false);
+                    continue;
+                }
+
+                indices[idx as usize] += 1;
+                for j in idx as usize + 1..r {
+                    indices[j] = indices[j - 1] + 1;
+                }
+                let result: Vec<_> = indices.iter().map(|&i| zelf.pool[i].clone()).collect();
+                zelf.produced.fetch_add(1);
+                return Ok(PyIterReturn::Return(vm.ctx.new_tuple(result).into()));
+            }
+        }
+    }
+
     #[pyattr]
     #[pyclass(name = "permutations")]
     #[derive(Debug, PyPayload)]
@@ -1668,6 +2440,7 @@ mod decl {
         cycles: PyRwLock<Vec<usize>>,         // One rollover counter per element in the result
         result: PyRwLock<Option<Vec<usize>>>, // Indexes of the most recently returned result
         r: AtomicCell<usize>,                 // Size of result tuple
+        produced: AtomicCell<usize>,          // Number of tuples already yielded
         exhausted: AtomicCell<bool>,          // Set when the iterator is exhausted
     }
 
@@ -1713,6 +2486,7 @@ mod decl {
                 cycles: PyRwLock::new((0..r.min(n)).map(|i| n - i).collect()),
                 result: PyRwLock::new(None),
                 r: AtomicCell::new(r),
+                produced: AtomicCell::new(0),
                 exhausted: AtomicCell::new(r > n),
             }
             .into_ref_with_type(vm, cls)
@@ -1722,6 +2496,13 @@ mod decl {
 
     #[pyclass(with(IterNext, Iterable, Constructor))]
     impl PyItertoolsPermutations {
+        #[pymethod]
+        fn __length_hint__(&self) -> usize {
+            let total = falling_factorial(self.pool.len(), self.r.load());
+            let produced = BigInt::from(self.produced.load());
+            length_hint_saturating((total - produced).max(BigInt::from(0)))
+        }
+
         #[pymethod]
         fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyRef<PyTuple> {
             vm.new_tuple((
@@ -1745,6 +2526,7 @@ mod decl {
 
             if n == 0 {
                 zelf.exhausted.store(true);
+                zelf.produced.fetch_add(1);
                 return Ok(PyIterReturn::Return(vm.new_tuple(()).into()));
             }
 
@@ -1789,6 +2571,7 @@ mod decl {
                 *result = Some((0..r).collect());
             }
 
+            zelf.produced.fetch_add(1);
             Ok(PyIterReturn::Return(
                 vm.ctx
                     .new_tuple(
@@ -1887,6 +2670,300 @@ mod decl {
         }
     }
 
+    /// One input's most-recently-pulled value in a [`PyItertoolsKmerge`]
+    /// merge, paired with its already-computed sort key and the index of the
+    /// input iterator it came from (used to break ties in favour of earlier
+    /// inputs, keeping the merge stable).
+    #[derive(Debug)]
+    struct KmergeEntry {
+        key: PyObjectRef,
+        value: PyObjectRef,
+        input_index: usize,
+    }
+
+    #[pyattr]
+    #[pyclass(name = "kmerge")]
+    #[derive(Debug, PyPayload)]
+    struct PyItertoolsKmerge {
+        iterables: Vec<PyIter>,
+        key_func: PyRwLock<Option<PyObjectRef>>,
+        reverse: AtomicCell<bool>,
+        entries: PyRwLock<Vec<KmergeEntry>>,
+        started: AtomicCell<bool>,
+    }
+
+    #[derive(FromArgs)]
+    struct KmergeArgs {
+        #[pyarg(named, optional)]
+        key: OptionalOption<PyObjectRef>,
+        #[pyarg(named, optional)]
+        reverse: OptionalArg<ArgIntoBool>,
+    }
+
+    impl Constructor for PyItertoolsKmerge {
+        type Args = (PosArgs<PyIter>, KmergeArgs);
+
+        fn py_new(cls: PyTypeRef, (iterables, args): Self::Args, vm: &VirtualMachine) -> PyResult {
+            Self {
+                iterables: iterables.into_vec(),
+                key_func: PyRwLock::new(args.key.flatten()),
+                reverse: AtomicCell::new(args.reverse.map(|b| *b).unwrap_or(false)),
+                entries: PyRwLock::new(Vec::new()),
+                started: AtomicCell::new(false),
+            }
+            .into_ref_with_type(vm, cls)
+            .map(Into::into)
+        }
+    }
+
+    #[pyclass(with(IterNext, Iterable, Constructor), flags(BASETYPE))]
+    impl PyItertoolsKmerge {
+        fn make_key(&self, value: &PyObjectRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            match &*self.key_func.read() {
+                Some(f) => f.call((value.clone(),), vm),
+                None => Ok(value.clone()),
+            }
+        }
+
+        /// Pull the first value out of every input, building the initial set
+        /// of entries that `next()` scans for the one to emit. Deferred to the
+        /// first `next()`/`__reduce__` call
+        /// so that merely constructing a `kmerge` doesn't touch the inputs.
+        fn ensure_started(&self, vm: &VirtualMachine) -> PyResult<()> {
+            if self.started.swap(true) {
+                return Ok(());
+            }
+            let mut entries = self.entries.write();
+            for (input_index, iterable) in self.iterables.iter().enumerate() {
+                if let PyIterReturn::Return(value) = iterable.next(vm)? {
+                    let key = self.make_key(&value, vm)?;
+                    entries.push(KmergeEntry {
+                        key,
+                        value,
+                        input_index,
+                    });
+                }
+            }
+            Ok(())
+        }
+
+        /// Whether `a` should be emitted before `b`: smaller key (or larger,
+        /// when `reverse`), with ties broken by input index for stability.
+        fn entry_precedes(
+            vm: &VirtualMachine,
+            reverse: bool,
+            a: &KmergeEntry,
+            b: &KmergeEntry,
+        ) -> PyResult<bool> {
+            let op = if reverse {
+                PyComparisonOp::Gt
+            } else {
+                PyComparisonOp::Lt
+            };
+            if vm.bool_cmp(&a.key, &b.key, op)? {
+                return Ok(true);
+            }
+            if vm.bool_cmp(&b.key, &a.key, op)? {
+                return Ok(false);
+            }
+            Ok(a.input_index < b.input_index)
+        }
+
+        #[pymethod]
+        fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult<PyTupleRef> {
+            zelf.ensure_started(vm)?;
+            let cls = zelf.class().to_owned();
+            let iterables: Vec<PyObjectRef> = zelf
+                .iterables
+                .iter()
+                .map(|i| i.clone().to_pyobject(vm))
+                .collect();
+            let key = zelf.key_func.read().clone().unwrap_or_else(|| vm.ctx.none());
+            let entries = zelf.entries.read();
+            let entry_tuples: Vec<PyObjectRef> = entries
+                .iter()
+                .map(|e| {
+                    vm.new_tuple((e.key.clone(), e.value.clone(), e.input_index))
+                        .into()
+                })
+                .collect();
+            Ok(vm.new_tuple((
+                cls,
+                vm.new_tuple(iterables),
+                vm.new_tuple((key, zelf.reverse.load(), vm.ctx.new_tuple(entry_tuples))),
+            )))
+        }
+
+        #[pymethod]
+        fn __setstate__(zelf: PyRef<Self>, state: PyTupleRef, vm: &VirtualMachine) -> PyResult<()> {
+            let parts = state.as_slice();
+            if parts.len() != 3 {
+                return Err(vm.new_type_error(format!(
+                    "function takes exactly 3 arguments ({} given)",
+                    parts.len()
+                )));
+            }
+            *zelf.key_func.write() = (!vm.is_none(&parts[0])).then(|| parts[0].clone());
+            zelf.reverse.store(parts[1].clone().try_to_bool(vm)?);
+            let entry_tuples = PyTupleRef::try_from_object(vm, parts[2].clone())?;
+            let mut restored = Vec::new();
+            for item in entry_tuples.as_slice() {
+                let entry = PyTupleRef::try_from_object(vm, item.clone())?;
+                let fields = entry.as_slice();
+                if fields.len() != 3 {
+                    return Err(vm.new_type_error("invalid kmerge entry state"));
+                }
+                restored.push(KmergeEntry {
+                    key: fields[0].clone(),
+                    value: fields[1].clone(),
+                    input_index: fields[2].clone().try_to_value(vm)?,
+                });
+            }
+            *zelf.entries.write() = restored;
+            zelf.started.store(true);
+            Ok(())
+        }
+    }
+
+    impl SelfIter for PyItertoolsKmerge {}
+
+    impl IterNext for PyItertoolsKmerge {
+        fn next(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyIterReturn> {
+            zelf.ensure_started(vm)?;
+            let mut entries = zelf.entries.write();
+            if entries.is_empty() {
+                return Ok(PyIterReturn::StopIteration(None));
+            }
+            let reverse = zelf.reverse.load();
+            let mut best = 0usize;
+            for i in 1..entries.len() {
+                if Self::entry_precedes(vm, reverse, &entries[i], &entries[best])? {
+                    best = i;
+                }
+            }
+            let entry = entries.remove(best);
+            let input_index = entry.input_index;
+            match zelf.iterables[input_index].next(vm)? {
+                PyIterReturn::Return(value) => {
+                    let key = zelf.make_key(&value, vm)?;
+                    entries.push(KmergeEntry {
+                        key,
+                        value,
+                        input_index,
+                    });
+                }
+                PyIterReturn::StopIteration(_) => {}
+            }
+            Ok(PyIterReturn::Return(entry.value))
+        }
+    }
+
+    #[pyattr]
+    #[pyclass(name = "intersperse")]
+    #[derive(Debug, PyPayload)]
+    struct PyItertoolsIntersperse {
+        iterator: PyIter,
+        separator: PyObjectRef,
+        /// The element already pulled out of `iterator` to check whether one
+        /// more separator is due, held until its own turn to be emitted.
+        peeked: PyRwLock<Option<PyObjectRef>>,
+        /// Set once `peeked` holds a real upcoming element, so the next call
+        /// emits `separator` instead of pulling further.
+        pending_sep: AtomicCell<bool>,
+    }
+
+    #[derive(FromArgs)]
+    struct IntersperseNewArgs {
+        #[pyarg(positional)]
+        iterable: PyIter,
+        #[pyarg(positional)]
+        separator: PyObjectRef,
+    }
+
+    impl Constructor for PyItertoolsIntersperse {
+        type Args = IntersperseNewArgs;
+
+        fn py_new(
+            cls: PyTypeRef,
+            Self::Args {
+                iterable,
+                separator,
+            }: Self::Args,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            Self {
+                iterator: iterable,
+                separator,
+                peeked: PyRwLock::new(None),
+                pending_sep: AtomicCell::new(false),
+            }
+            .into_ref_with_type(vm, cls)
+            .map(Into::into)
+        }
+    }
+
+    #[pyclass(with(IterNext, Iterable, Constructor))]
+    impl PyItertoolsIntersperse {
+        #[pymethod]
+        fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyTupleRef {
+            let class = zelf.class().to_owned();
+            let tup = vm.new_tuple((zelf.iterator.clone(), zelf.separator.clone()));
+            let peeked_tup: PyObjectRef = match &*zelf.peeked.read() {
+                Some(v) => vm.new_tuple((v.clone(),)).into(),
+                None => vm.ctx.empty_tuple.clone().into(),
+            };
+            let state = vm.new_tuple((zelf.pending_sep.load(), peeked_tup));
+            vm.new_tuple((class, tup, state))
+        }
+
+        #[pymethod]
+        fn __setstate__(zelf: PyRef<Self>, state: PyTupleRef, vm: &VirtualMachine) -> PyResult<()> {
+            let args = state.as_slice();
+            if args.len() != 2 {
+                return Err(vm.new_type_error("invalid intersperse state"));
+            }
+            let pending_sep = args[0].clone().try_to_bool(vm)?;
+            let peeked_tup = PyTupleRef::try_from_object(vm, args[1].clone())?;
+            let peeked = match peeked_tup.as_slice() {
+                [] => None,
+                [v] => Some(v.clone()),
+                _ => return Err(vm.new_type_error("invalid intersperse state")),
+            };
+            zelf.pending_sep.store(pending_sep);
+            *zelf.peeked.write() = peeked;
+            Ok(())
+        }
+    }
+
+    impl SelfIter for PyItertoolsIntersperse {}
+
+    impl IterNext for PyItertoolsIntersperse {
+        fn next(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyIterReturn> {
+            if zelf.pending_sep.load() {
+                zelf.pending_sep.store(false);
+                return Ok(PyIterReturn::Return(zelf.separator.clone()));
+            }
+
+            let mut peeked = zelf.peeked.write();
+            let value = match peeked.take() {
+                Some(v) => v,
+                None => raise_if_stop!(zelf.iterator.next(vm)?),
+            };
+
+            match zelf.iterator.next(vm)? {
+                PyIterReturn::Return(next_val) => {
+                    *peeked = Some(next_val);
+                    zelf.pending_sep.store(true);
+                }
+                PyIterReturn::StopIteration(_) => {
+                    *peeked = None;
+                }
+            }
+
+            Ok(PyIterReturn::Return(value))
+        }
+    }
+
     #[pyattr]
     #[pyclass(name = "pairwise")]
     #[derive(Debug, PyPayload)]
@@ -2011,4 +3088,155 @@ mod decl {
             }
         }
     }
+
+    #[pyattr]
+    #[pyclass(name = "dedup")]
+    #[derive(Debug, PyPayload)]
+    struct PyItertoolsDedup {
+        iterable: PyIter,
+        key_func: Option<PyObjectRef>,
+        last_key: PyRwLock<Option<PyObjectRef>>,
+    }
+
+    #[derive(FromArgs)]
+    struct DedupNewArgs {
+        #[pyarg(positional)]
+        iterable: PyIter,
+        #[pyarg(any, optional)]
+        key: OptionalOption<PyObjectRef>,
+    }
+
+    impl Constructor for PyItertoolsDedup {
+        type Args = DedupNewArgs;
+
+        fn py_new(
+            cls: PyTypeRef,
+            Self::Args { iterable, key }: Self::Args,
+            vm: &VirtualMachine,
+        ) -> PyResult {
+            Self {
+                iterable,
+                key_func: key.flatten(),
+                last_key: PyRwLock::new(None),
+            }
+            .into_ref_with_type(vm, cls)
+            .map(Into::into)
+        }
+    }
+
+    #[pyclass(with(IterNext, Iterable, Constructor), flags(BASETYPE))]
+    impl PyItertoolsDedup {
+        #[pymethod]
+        fn __reduce__(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyTupleRef {
+            let cls = zelf.class().to_owned();
+            let key = zelf.key_func.clone().unwrap_or_else(|| vm.ctx.none());
+            let args = vm.new_tuple((zelf.iterable.clone(), key));
+            match zelf.last_key.read().clone() {
+                Some(last_key) => vm.new_tuple((cls, args, (last_key,))),
+                None => vm.new_tuple((cls, args)),
+            }
+        }
+
+        #[pymethod]
+        fn __setstate__(zelf: PyRef<Self>, state: PyTupleRef, vm: &VirtualMachine) -> PyResult<()> {
+            let args = state.as_slice();
+            if args.len() != 1 {
+                return Err(vm.new_type_error(format!(
+                    "function takes exactly 1 argument ({} given)",
+                    args.len()
+                )));
+            }
+            *zelf.last_key.write() = Some(args[0].clone());
+            Ok(())
+        }
+    }
+
+    impl SelfIter for PyItertoolsDedup {}
+
+    impl IterNext for PyItertoolsDedup {
+        fn next(zelf: &Py<Self>, vm: &VirtualMachine) -> PyResult<PyIterReturn> {
+            loop {
+                let obj = raise_if_stop!(zelf.iterable.next(vm)?);
+                let key = match &zelf.key_func {
+                    Some(f) => f.call((obj.clone(),), vm)?,
+                    None => obj.clone(),
+                };
+                let mut last_key = zelf.last_key.write();
+                if let Some(prev) = last_key.as_ref() {
+                    if vm.bool_eq(prev, &key)? {
+                        continue;
+                    }
+                }
+                *last_key = Some(key);
+                drop(last_key);
+                return Ok(PyIterReturn::Return(obj));
+            }
+        }
+    }
+
+    #[derive(FromArgs)]
+    struct TreeReduceArgs {
+        #[pyarg(positional)]
+        iterable: PyIter,
+        #[pyarg(positional)]
+        function: ArgCallable,
+    }
+
+    /// Combine elements in a balanced binary-tree order rather than
+    /// left-to-right: limits recursion-like depth and, for floats, reduces
+    /// rounding error versus a linear `functools.reduce`.
+    #[pyfunction]
+    fn tree_reduce(args: TreeReduceArgs, vm: &VirtualMachine) -> PyResult {
+        let TreeReduceArgs { iterable, function } = args;
+
+        // Stack of (value, height); combine adjacent equal-height entries
+        // eagerly so the stack never holds more than `log2(n) + 1` values.
+        let mut stack: Vec<(PyObjectRef, u32)> = Vec::new();
+
+        while let PyIterReturn::Return(obj) = iterable.next(vm)? {
+            let mut value = obj;
+            let mut height = 0u32;
+            while let Some(&(_, top_height)) = stack.last() {
+                if top_height != height {
+                    break;
+                }
+                let (top_value, _) = stack.pop().unwrap();
+                value = function.invoke((top_value, value), vm)?;
+                height += 1;
+            }
+            stack.push((value, height));
+        }
+
+        let mut iter = stack.into_iter().rev();
+        let Some((mut acc, _)) = iter.next() else {
+            return Err(vm.new_type_error("tree_reduce() of empty iterable with no initial value"));
+        };
+        for (value, _) in iter {
+            acc = function.invoke((value, acc), vm)?;
+        }
+        Ok(acc)
+    }
+
+    #[derive(FromArgs)]
+    struct GroupingMapArgs {
+        #[pyarg(positional)]
+        iterable: PyIter,
+        #[pyarg(positional)]
+        key: ArgCallable,
+    }
+
+    /// Build a [`PyItertoolsGroupingMap`] over the *entire* input in one
+    /// pass, computing `key(item)` for each element as it arrives rather
+    /// than requiring pre-built `(key, value)` pairs the way constructing
+    /// `grouping_map` directly does.
+    #[pyfunction]
+    fn grouping_map(args: GroupingMapArgs, vm: &VirtualMachine) -> PyResult {
+        let GroupingMapArgs { iterable, key } = args;
+        let groups = vm.ctx.new_dict();
+        while let PyIterReturn::Return(item) = iterable.next(vm)? {
+            let k = key.invoke((item.clone(),), vm)?;
+            PyItertoolsGroupingMap::insert_into(&groups, k, item, vm)?;
+        }
+        Ok(PyItertoolsGroupingMap { groups }.into_ref(&vm.ctx).into())
+    }
 }